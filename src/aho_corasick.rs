@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+
+/// A match of one pattern in a haystack, as byte offsets.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Match {
+	/// Index of the pattern in the order the patterns were given.
+	pub pattern: usize,
+	pub start: usize,
+	pub end: usize,
+}
+
+
+/// Finds every occurrence of a set of patterns in a single pass over the haystack, including overlapping ones.
+///
+/// Patterns are compiled into a deterministic automaton over bytes, so each byte of the haystack costs one
+/// table lookup no matter how many patterns there are. Patterns are valid UTF-8, so matches in a `&str` always
+/// start and end on char boundaries.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick {
+	/// The next state for every state and byte.
+	transitions: Vec<[u32; 256]>,
+	/// Patterns that end at each state, longest first.
+	outputs: Vec<Vec<usize>>,
+	pattern_lengths: Vec<usize>,
+}
+
+impl AhoCorasick {
+	pub fn new<'a>(patterns: impl IntoIterator<Item=&'a str>) -> AhoCorasick {
+		let mut automaton = AhoCorasick {
+			transitions: vec![[0; 256]],
+			outputs: vec![Vec::new()],
+			pattern_lengths: Vec::new(),
+		};
+
+		// Build a trie of every pattern, where 0 means no edge yet. Nothing can transition back to the root,
+		// so 0 is never a real edge until failure transitions are filled in below
+		for (pattern_index, pattern) in patterns.into_iter().enumerate() {
+			let mut state = 0;
+
+			for &byte in pattern.as_bytes() {
+				state = match automaton.transitions[state][byte as usize] {
+					0 => {
+						let new_state = automaton.transitions.len();
+						automaton.transitions.push([0; 256]);
+						automaton.outputs.push(Vec::new());
+						automaton.transitions[state][byte as usize] = new_state as u32;
+						new_state
+					}
+
+					next_state => next_state as usize,
+				};
+			}
+
+			automaton.outputs[state].push(pattern_index);
+			automaton.pattern_lengths.push(pattern.len());
+		}
+
+		// Breadth first, so every state's failure state is complete before the state itself.
+		// Missing edges are replaced by the failure state's edge, turning the trie into a complete automaton
+		let mut failures = vec![0; automaton.transitions.len()];
+		let mut queue: VecDeque<usize> = automaton.transitions[0].iter()
+			.filter(|&&next_state| next_state != 0)
+			.map(|&next_state| next_state as usize)
+			.collect();
+
+		while let Some(state) = queue.pop_front() {
+			let failure = failures[state];
+
+			let inherited_outputs = automaton.outputs[failure].clone();
+			automaton.outputs[state].extend(inherited_outputs);
+
+			for byte in 0..256 {
+				let next_state = automaton.transitions[state][byte] as usize;
+
+				if next_state == 0 {
+					automaton.transitions[state][byte] = automaton.transitions[failure][byte];
+				} else {
+					failures[next_state] = automaton.transitions[failure][byte] as usize;
+					queue.push_back(next_state);
+				}
+			}
+		}
+
+		automaton
+	}
+
+	/// Every match in `haystack`, in order of where they end. Matches ending at the same place come longest first.
+	pub fn find_overlapping<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item=Match> + 'a {
+		haystack.bytes()
+			.enumerate()
+			.scan(0, move |state, (index, byte)| {
+				*state = self.transitions[*state][byte as usize] as usize;
+				Some((index + 1, *state))
+			})
+			.flat_map(move |(end, state)| {
+				self.outputs[state].iter()
+					.map(move |&pattern| Match {
+						pattern,
+						start: end - self.pattern_lengths[pattern],
+						end,
+					})
+			})
+	}
+}
+
+
+
+#[test]
+fn test_find_overlapping() {
+	let automaton = AhoCorasick::new(["he", "she", "his", "hers"]);
+	let matches: Vec<_> = automaton.find_overlapping("ushers")
+		.map(|m| (m.pattern, m.start, m.end))
+		.collect();
+
+	assert_eq!(matches, &[(1, 1, 4), (0, 2, 4), (3, 2, 6)]);
+
+	let automaton = AhoCorasick::new(["one", "eight", "two"]);
+	let matches: Vec<_> = automaton.find_overlapping("oneightwone")
+		.map(|m| m.pattern)
+		.collect();
+
+	assert_eq!(matches, &[0, 1, 2, 0]);
+	assert_eq!(automaton.find_overlapping("none at all").count(), 1);
+	assert_eq!(automaton.find_overlapping("").count(), 0);
+
+	// Multi-byte text, and patterns
+	let automaton = AhoCorasick::new(["drei", "zwölf"]);
+	let matches: Vec<_> = automaton.find_overlapping("ßzwölfdrei").collect();
+	assert_eq!(matches, &[
+		Match { pattern: 1, start: 2, end: 8 },
+		Match { pattern: 0, start: 8, end: 12 },
+	]);
+}
@@ -0,0 +1,112 @@
+use crate::input::{InputError, InputSource, default_input_path, load_input};
+
+use std::path::PathBuf;
+
+
+/// Recorded correct answers for one input file.
+///
+/// Stored next to the input with an `.answers` extension, as lines of `<day> <part> <answer>`.
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedAnswers {
+	entries: Vec<(u32, u32, String)>,
+}
+
+impl ExpectedAnswers {
+	pub fn parse(origin: &str, answers_str: &str) -> Result<ExpectedAnswers, InputError> {
+		let mut entries = Vec::new();
+
+		for (line_index, line) in answers_str.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue
+			}
+
+			let malformed = || InputError::Malformed {
+				origin: origin.to_owned(),
+				line_number: line_index + 1,
+				expected: "<day> <part> <answer>",
+			};
+
+			let mut it = line.splitn(3, char::is_whitespace);
+			let day = it.next().and_then(|s| s.parse().ok()).ok_or_else(malformed)?;
+			let part = it.next().and_then(|s| s.parse().ok()).ok_or_else(malformed)?;
+			let answer = it.next().map(str::trim).filter(|s| !s.is_empty()).ok_or_else(malformed)?;
+
+			entries.push((day, part, answer.to_owned()));
+		}
+
+		Ok(ExpectedAnswers { entries })
+	}
+
+	pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+		self.entries.iter()
+			.find(|(d, p, _)| *d == day && *p == part)
+			.map(|(_, _, answer)| answer.as_str())
+	}
+
+	pub fn check(&self, day: u32, part: u32, answer: &str) -> CheckStatus {
+		match self.get(day, part) {
+			Some(expected) if expected == answer => CheckStatus::Pass,
+			Some(expected) => CheckStatus::Fail { expected: expected.to_owned() },
+			None => CheckStatus::Unknown,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+	Pass,
+	Fail { expected: String },
+	Unknown,
+}
+
+impl CheckStatus {
+	pub fn name(&self) -> &'static str {
+		match self {
+			CheckStatus::Pass => "pass",
+			CheckStatus::Fail{..} => "fail",
+			CheckStatus::Unknown => "unknown",
+		}
+	}
+}
+
+
+/// The answers file that belongs to `source`, if it can have one.
+pub fn answers_path(day: u32, source: &InputSource) -> Option<PathBuf> {
+	match source {
+		InputSource::Default => Some(default_input_path(day).with_extension("answers")),
+		InputSource::File(path) => Some(path.with_extension("answers")),
+		InputSource::Stdin => None,
+	}
+}
+
+/// Loads the answers recorded for `source`. A missing answers file just means every answer is unknown.
+pub fn load_answers(day: u32, source: &InputSource) -> Result<ExpectedAnswers, InputError> {
+	let Some(path) = answers_path(day, source)
+		else { return Ok(ExpectedAnswers::default()) };
+
+	match load_input(day, &InputSource::File(path.clone())) {
+		Ok(answers_str) => ExpectedAnswers::parse(&path.display().to_string(), &answers_str),
+		Err(InputError::NotFound(_)) => Ok(ExpectedAnswers::default()),
+		Err(error) => Err(error),
+	}
+}
+
+
+#[test]
+fn test_parse_answers() {
+	let answers = ExpectedAnswers::parse("test", "# day part answer\n5 1 35\n\n5 2 46\n").unwrap();
+
+	assert_eq!(answers.get(5, 1), Some("35"));
+	assert_eq!(answers.get(5, 2), Some("46"));
+	assert_eq!(answers.get(4, 1), None);
+
+	assert_eq!(answers.check(5, 1, "35"), CheckStatus::Pass);
+	assert_eq!(answers.check(5, 2, "47"), CheckStatus::Fail { expected: "46".into() });
+	assert_eq!(answers.check(4, 1, "13"), CheckStatus::Unknown);
+
+	assert!(ExpectedAnswers::parse("test", "5 x 35").is_err());
+	assert!(ExpectedAnswers::parse("test", "5 1").is_err());
+}
@@ -0,0 +1,142 @@
+use crate::{Day, ParseError};
+use crate::output::OutputMode;
+
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+
+/// Repeated measurements of a single phase.
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+	samples: Vec<Duration>,
+}
+
+impl Timings {
+	pub fn push(&mut self, sample: Duration) {
+		self.samples.push(sample);
+	}
+
+	pub fn runs(&self) -> usize {
+		self.samples.len()
+	}
+
+	pub fn min(&self) -> Duration {
+		self.samples.iter().copied().min().unwrap_or_default()
+	}
+
+	pub fn median(&self) -> Duration {
+		if self.samples.is_empty() {
+			return Duration::ZERO
+		}
+
+		let mut sorted = self.samples.clone();
+		sorted.sort();
+
+		// For an even number of samples, average the two middle samples
+		let lower = (sorted.len() - 1) / 2;
+		let upper = sorted.len() / 2;
+		(sorted[lower] + sorted[upper]) / 2
+	}
+
+	pub fn mean(&self) -> Duration {
+		if self.samples.is_empty() {
+			return Duration::ZERO
+		}
+
+		self.samples.iter().sum::<Duration>() / self.samples.len() as u32
+	}
+}
+
+
+#[derive(Debug, Clone)]
+pub struct DayBenchmark {
+	pub day: u32,
+	pub parse: Timings,
+	pub parts: Vec<(u32, Timings)>,
+}
+
+/// Parses `input` and solves each of `parts` `runs` times, timing every phase separately.
+pub fn benchmark_day(day: &Day, parts: &[u32], input: &str, runs: usize) -> Result<DayBenchmark, ParseError> {
+	let mut benchmark = DayBenchmark {
+		day: day.number,
+		parse: Timings::default(),
+		parts: parts.iter().map(|&part| (part, Timings::default())).collect(),
+	};
+
+	for _ in 0..runs {
+		let start = Instant::now();
+		let parsed = black_box(day.solution.parse(black_box(input))?);
+		benchmark.parse.push(start.elapsed());
+
+		for (part, timings) in &mut benchmark.parts {
+			let start = Instant::now();
+			black_box(day.solution.solve(&*parsed, *part));
+			timings.push(start.elapsed());
+		}
+	}
+
+	Ok(benchmark)
+}
+
+
+pub fn write_benchmarks(mode: OutputMode, benchmarks: &[DayBenchmark], out: &mut impl Write) -> io::Result<()> {
+	let phases = benchmarks.iter()
+		.flat_map(|benchmark| {
+			let parts = benchmark.parts.iter()
+				.map(|(part, timings)| (format!("part {part}"), timings));
+
+			std::iter::once(("parse".to_owned(), &benchmark.parse))
+				.chain(parts)
+				.map(|(phase, timings)| (benchmark.day, phase, timings))
+		});
+
+	match mode {
+		OutputMode::Plain => {
+			for (day, phase, timings) in phases {
+				writeln!(out, "{day}\t{phase}\t{}\t{}\t{}", timings.min().as_nanos(), timings.median().as_nanos(), timings.mean().as_nanos())?;
+			}
+		}
+
+		OutputMode::Human => {
+			writeln!(out, "{:<6} {:<8} {:>12} {:>12} {:>12} {:>6}", "day", "phase", "min", "median", "mean", "runs")?;
+
+			for (day, phase, timings) in phases {
+				let min = format!("{:.2?}", timings.min());
+				let median = format!("{:.2?}", timings.median());
+				let mean = format!("{:.2?}", timings.mean());
+				writeln!(out, "{day:<6} {phase:<8} {min:>12} {median:>12} {mean:>12} {:>6}", timings.runs())?;
+			}
+		}
+
+		OutputMode::Json => {
+			let objects: Vec<_> = phases
+				.map(|(day, phase, timings)| format!(
+					"\t{{\"day\": {day}, \"phase\": \"{phase}\", \"runs\": {}, \"min_us\": {}, \"median_us\": {}, \"mean_us\": {}}}",
+					timings.runs(), timings.min().as_micros(), timings.median().as_micros(), timings.mean().as_micros()
+				))
+				.collect();
+
+			writeln!(out, "[\n{}\n]", objects.join(",\n"))?;
+		}
+	}
+
+	Ok(())
+}
+
+
+#[test]
+fn test_timings_statistics() {
+	let mut timings = Timings::default();
+	for ms in [5, 1, 3, 7] {
+		timings.push(Duration::from_millis(ms));
+	}
+
+	assert_eq!(timings.runs(), 4);
+	assert_eq!(timings.min(), Duration::from_millis(1));
+	assert_eq!(timings.median(), Duration::from_millis(4));
+	assert_eq!(timings.mean(), Duration::from_millis(4));
+
+	timings.push(Duration::from_millis(2));
+	assert_eq!(timings.median(), Duration::from_millis(3));
+}
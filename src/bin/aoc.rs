@@ -0,0 +1,286 @@
+use aoc_2023::*;
+use aoc_2023::answers::{CheckStatus, load_answers};
+use aoc_2023::bench::{benchmark_day, write_benchmarks};
+use aoc_2023::day1::{self, CalibrationReport, Vocabulary};
+use aoc_2023::day5::Day5;
+use aoc_2023::generate::generate;
+use aoc_2023::input::{InputSource, load_input};
+use aoc_2023::output::{OutputMode, PartResult, write_results};
+
+use std::time::Instant;
+
+
+const DEFAULT_BENCH_RUNS: usize = 20;
+const DEFAULT_GENERATE_SIZE: usize = 100;
+
+
+fn main() {
+	let mut args: Vec<String> = std::env::args().skip(1).collect();
+
+	if args.first().is_some_and(|arg| arg == "generate") {
+		args.remove(0);
+		run_generate(args);
+		return
+	}
+
+	let benchmark = args.first().is_some_and(|arg| arg == "bench");
+	if benchmark {
+		args.remove(0);
+	}
+
+	let check = take_flag(&mut args, &["--check", "-c"]);
+
+	let input_source = take_option(&mut args, &["--input", "-i"])
+		.map(|path| InputSource::from_arg(&path));
+
+	let output_mode = take_option(&mut args, &["--format", "-f"])
+		.map(|name| OutputMode::from_name(&name)
+			.unwrap_or_else(|| usage_error(&format!("unknown format '{name}'"))))
+		.unwrap_or_default();
+
+	let runs = take_option(&mut args, &["--runs", "-n"])
+		.map(|runs| runs.parse().ok().filter(|&runs| runs > 0)
+			.unwrap_or_else(|| usage_error(&format!("invalid run count '{runs}'"))))
+		.unwrap_or(DEFAULT_BENCH_RUNS);
+
+	let vocabulary_path = take_option(&mut args, &["--vocabulary"]);
+	let ignore_case = take_flag(&mut args, &["--ignore-case"]);
+	let report = take_flag(&mut args, &["--report"]);
+	let stages = take_flag(&mut args, &["--stages"]);
+
+	let args: Vec<&str> = args.iter().map(String::as_str).collect();
+	let (days, parts) = select_days(&args);
+
+	let vocabulary = (vocabulary_path.is_some() || ignore_case).then(|| {
+		if benchmark || days.iter().any(|day| day.number != 1) {
+			usage_error("--vocabulary and --ignore-case can only be used when running day 1");
+		}
+
+		if check {
+			usage_error("--check can't be used with a custom vocabulary");
+		}
+
+		load_vocabulary(vocabulary_path.as_deref(), ignore_case)
+	});
+
+	if days.len() > 1 && input_source.is_some() {
+		usage_error("--input can only be used with a single day");
+	}
+
+	if benchmark && check {
+		usage_error("--check can't be used with bench");
+	}
+
+	let input_source = input_source.unwrap_or_default();
+
+	if report {
+		if benchmark || check || days.len() != 1 || days[0].number != 1 {
+			usage_error("--report can only be used when running day 1 without --check");
+		}
+
+		let vocabulary = vocabulary.unwrap_or_else(|| load_vocabulary(None, false));
+		let lines = day1::parse_with(&load_day_input(days[0], &input_source), &vocabulary)
+			.unwrap_or_else(|error| exit_with_error(days[0], error));
+
+		print!("{}", CalibrationReport::new(&lines, &vocabulary));
+		return
+	}
+
+	if stages {
+		if benchmark || check || days.len() != 1 || days[0].number != 5 {
+			usage_error("--stages can only be used when running day 5 without --check");
+		}
+
+		let puzzle = Day5::parse(&load_day_input(days[0], &input_source))
+			.unwrap_or_else(|error| exit_with_error(days[0], error));
+
+		// Parsing already checked there are maps leading from seed to location
+		let almanac = &puzzle.almanac;
+		for stage in almanac.range_stages("seed", "location", almanac.seed_ranges().iter().copied()).unwrap() {
+			println!("{stage}");
+		}
+
+		return
+	}
+
+	let mut stdout = std::io::stdout().lock();
+
+	if benchmark {
+		let benchmarks: Vec<_> = days.into_iter()
+			.map(|day| benchmark_day(day, &parts, &load_day_input(day, &input_source), runs)
+				.unwrap_or_else(|error| exit_with_error(day, error)))
+			.collect();
+
+		write_benchmarks(output_mode, &benchmarks, &mut stdout)
+			.expect("Failed to write benchmarks");
+
+	} else {
+		let mut results = Vec::new();
+
+		for day in days {
+			let day_results_start = results.len();
+			let input = load_day_input(day, &input_source);
+
+			match &vocabulary {
+				Some(vocabulary) => run_day1_with_vocabulary(day, &parts, &input, vocabulary, &mut results),
+				None => run_day(day, &parts, &input, &mut results),
+			}
+
+			if check {
+				let expected = load_answers(day.number, &input_source)
+					.unwrap_or_else(|error| exit_with_error(day, error));
+
+				for result in &mut results[day_results_start..] {
+					result.check = Some(expected.check(result.day, result.part, &result.answer));
+				}
+			}
+		}
+
+		write_results(output_mode, &results, &mut stdout)
+			.expect("Failed to write results");
+
+		if results.iter().any(|result| matches!(result.check, Some(CheckStatus::Fail{..}))) {
+			std::process::exit(1);
+		}
+	}
+}
+
+
+/// Writes a random input for one day to stdout, so it can be piped back in with `--input -`.
+fn run_generate(mut args: Vec<String>) {
+	let size = take_option(&mut args, &["--size", "-s"])
+		.map(|size| size.parse().ok()
+			.unwrap_or_else(|| usage_error(&format!("invalid size '{size}'"))))
+		.unwrap_or(DEFAULT_GENERATE_SIZE);
+
+	let seed = take_option(&mut args, &["--seed"])
+		.map(|seed| seed.parse().ok()
+			.unwrap_or_else(|| usage_error(&format!("invalid seed '{seed}'"))))
+		.unwrap_or(0);
+
+	let [day_str] = args.as_slice()
+		else { usage_error("generate expects a single day") };
+
+	let day = parse_day(day_str);
+	let input = generate(day.number, size, seed)
+		.unwrap_or_else(|| exit_with_error(day, "no generator for this day"));
+
+	print!("{input}");
+}
+
+fn run_day(day: &Day, parts: &[u32], input: &str, results: &mut Vec<PartResult>) {
+	let parsed = day.solution.parse(input)
+		.unwrap_or_else(|error| exit_with_error(day, error));
+
+	for warning in day.solution.warnings(&*parsed) {
+		eprintln!("warning: day {}: {warning}", day.number);
+	}
+
+	for &part in parts {
+		let start = Instant::now();
+		let answer = day.solution.solve(&*parsed, part).unwrap();
+		let elapsed = start.elapsed();
+
+		results.push(PartResult { day: day.number, part, answer, elapsed, check: None });
+	}
+}
+
+/// Like `run_day`, but reads spelled out digits with `vocabulary` instead of the English words.
+fn run_day1_with_vocabulary(day: &Day, parts: &[u32], input: &str, vocabulary: &Vocabulary, results: &mut Vec<PartResult>) {
+	let lines = day1::parse_with(input, vocabulary)
+		.unwrap_or_else(|error| exit_with_error(day, error));
+
+	for &part in parts {
+		let start = Instant::now();
+		let answer = match part {
+			1 => day1::Day1::part1(&lines),
+			_ => day1::part2_with(&lines, vocabulary),
+		};
+
+		let elapsed = start.elapsed();
+		results.push(PartResult { day: day.number, part, answer: answer.to_string(), elapsed, check: None });
+	}
+}
+
+/// Reads a vocabulary table from `path`, or uses the English words if there's no path.
+fn load_vocabulary(path: Option<&str>, ignore_case: bool) -> Vocabulary {
+	let Some(path) = path else {
+		return Vocabulary::new(day1::ENGLISH_WORDS, ignore_case)
+	};
+
+	let table_str = std::fs::read_to_string(path)
+		.unwrap_or_else(|error| usage_error(&format!("couldn't read vocabulary '{path}': {error}")));
+
+	Vocabulary::parse(&table_str, ignore_case)
+		.unwrap_or_else(|error| usage_error(&format!("vocabulary '{path}': {error}")))
+}
+
+fn load_day_input(day: &Day, input_source: &InputSource) -> String {
+	load_input(day.number, input_source)
+		.unwrap_or_else(|error| exit_with_error(day, error))
+}
+
+fn exit_with_error(day: &Day, error: impl std::fmt::Display) -> ! {
+	eprintln!("error: day {}: {error}", day.number);
+	std::process::exit(1)
+}
+
+/// Interprets the positional arguments as `all`, `<day>` or `<day> <part>`.
+fn select_days(args: &[&str]) -> (Vec<&'static Day>, Vec<u32>) {
+	match *args {
+		["all"] => (DAYS.iter().collect(), PARTS.to_vec()),
+
+		[day] => (vec![parse_day(day)], PARTS.to_vec()),
+
+		[day, part] => {
+			let day = parse_day(day);
+			let Some(part) = part.parse().ok().filter(|part| PARTS.contains(part))
+				else { usage_error(&format!("unknown part '{part}'")) };
+
+			(vec![day], vec![part])
+		}
+
+		_ => usage_error("expected a day and optional part, or 'all'"),
+	}
+}
+
+/// Removes `--flag` from `args` if present.
+fn take_flag(args: &mut Vec<String>, names: &[&str]) -> bool {
+	let Some(flag_index) = args.iter().position(|arg| names.contains(&arg.as_str()))
+		else { return false };
+
+	args.remove(flag_index);
+	true
+}
+
+/// Removes `--flag value` from `args` if present, and returns the value.
+fn take_option(args: &mut Vec<String>, names: &[&str]) -> Option<String> {
+	let flag_index = args.iter().position(|arg| names.contains(&arg.as_str()))?;
+
+	if flag_index + 1 >= args.len() {
+		usage_error(&format!("{} expects a value", args[flag_index]));
+	}
+
+	let value = args.remove(flag_index + 1);
+	args.remove(flag_index);
+	Some(value)
+}
+
+fn parse_day(day_str: &str) -> &'static Day {
+	day_str.parse().ok()
+		.and_then(find_day)
+		.unwrap_or_else(|| usage_error(&format!("unknown day '{day_str}'")))
+}
+
+fn usage_error(message: &str) -> ! {
+	eprintln!("error: {message}");
+	eprintln!("usage: aoc <day> [part] [--input <path>|-] [--check] [--format plain|human|json]");
+	eprintln!("       aoc all [--check] [--format plain|human|json]");
+	eprintln!("       aoc 1 [part] [--vocabulary <path>] [--ignore-case] [--input <path>|-] [--format plain|human|json]");
+	eprintln!("       aoc 1 --report [--vocabulary <path>] [--ignore-case] [--input <path>|-]");
+	eprintln!("       aoc 5 --stages [--input <path>|-]");
+	eprintln!("       aoc bench <day> [part] [--input <path>|-] [--runs <n>] [--format plain|human|json]");
+	eprintln!("       aoc bench all [--runs <n>] [--format plain|human|json]");
+	eprintln!("       aoc generate <day> [--size <n>] [--seed <n>]");
+	std::process::exit(1)
+}
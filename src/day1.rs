@@ -0,0 +1,473 @@
+use crate::{Solution, ParseError};
+use crate::aho_corasick::AhoCorasick;
+use crate::parse::{parse_lines, parse_number};
+
+use std::fmt;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+
+pub struct Day1;
+
+impl Solution for Day1 {
+	type Parsed = Vec<String>;
+	type Part1 = u64;
+	type Part2 = u64;
+
+	fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+		parse_with(input, Vocabulary::english())
+	}
+
+	/// Lines with only spelled out digits have no value in part 1, and are skipped.
+	fn part1(lines: &Vec<String>) -> u64 {
+		lines.iter()
+			.filter_map(|line| part1(line))
+			.map(u64::from)
+			.sum()
+	}
+
+	fn part2(lines: &Vec<String>) -> u64 {
+		lines.iter()
+			.filter_map(|line| part2(line))
+			.map(u64::from)
+			.sum()
+	}
+}
+
+/// Every line must have at least one digit in `vocabulary`, so part 2 always has a value for it.
+pub fn parse_with(input: &str, vocabulary: &Vocabulary) -> Result<Vec<String>, ParseError> {
+	parse_lines(input, |line| {
+		vocabulary.calibration_value(line)
+			.map(|_| line.to_owned())
+			.ok_or_else(|| ParseError::new(line, line, "a line containing a digit"))
+	})
+}
+
+/// The sum of every line's calibration value, reading digits with `vocabulary`.
+pub fn part2_with(lines: &[String], vocabulary: &Vocabulary) -> u64 {
+	lines.iter()
+		.filter_map(|line| vocabulary.calibration_value(line))
+		.map(u64::from)
+		.sum()
+}
+
+
+pub const ENGLISH_WORDS: [(&str, u32); 9] = [
+	("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
+
+
+/// The words that can stand in for numbers, and the values they stand for.
+/// Numerals `0` to `9` are always included.
+///
+/// Words with multi-digit values, like `thirteen`, count as their first digit at the start of a line
+/// and as their last digit at the end.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+	/// Every pattern and its value, indexed by pattern in `matcher`.
+	patterns: Vec<(String, u32)>,
+	ignore_case: bool,
+	matcher: AhoCorasick,
+}
+
+impl Vocabulary {
+	pub fn new<S: AsRef<str>>(words: impl IntoIterator<Item=(S, u32)>, ignore_case: bool) -> Vocabulary {
+		let numerals = (0..10).map(|value| (value.to_string(), value));
+
+		let words = words.into_iter()
+			.map(|(word, value)| match ignore_case {
+				true => (word.as_ref().to_lowercase(), value),
+				false => (word.as_ref().to_owned(), value),
+			});
+
+		let patterns: Vec<(String, u32)> = numerals.chain(words).collect();
+		let matcher = AhoCorasick::new(patterns.iter().map(|(pattern, _)| pattern.as_str()));
+
+		Vocabulary { patterns, ignore_case, matcher }
+	}
+
+	/// `one` to `nine`, matched exactly.
+	pub fn english() -> &'static Vocabulary {
+		static ENGLISH: OnceLock<Vocabulary> = OnceLock::new();
+
+		ENGLISH.get_or_init(|| Vocabulary::new(ENGLISH_WORDS, false))
+	}
+
+	/// Reads a table with a word and its value on each line, separated by whitespace.
+	/// Words can contain spaces, since only the last field is the value. Blank lines and lines starting with `#` are ignored.
+	pub fn parse(table_str: &str, ignore_case: bool) -> Result<Vocabulary, ParseError> {
+		let entries = parse_lines(table_str, |line| {
+			if line.trim().is_empty() || line.trim_start().starts_with('#') {
+				return Ok(None)
+			}
+
+			let (word, value_str) = line.trim_end().rsplit_once(char::is_whitespace)
+				.ok_or_else(|| ParseError::at_end(line, "a value after the word"))?;
+
+			let word = word.trim();
+			if word.is_empty() {
+				return Err(ParseError::new(line, &line[..0], "a word"))
+			}
+
+			Ok(Some((word.to_owned(), parse_number(line, value_str)?)))
+		})?;
+
+		Ok(Vocabulary::new(entries.into_iter().flatten(), ignore_case))
+	}
+
+	/// Every numeral and word in `line`, in order of where they start. Tokens can overlap, as in `eightwo`.
+	pub fn tokens(&self, line: &str) -> Vec<DigitToken> {
+		let token = |pattern: usize, span: Range<usize>| DigitToken {
+			span,
+			kind: if pattern < 10 { TokenKind::Numeral } else { TokenKind::Word },
+			value: self.patterns[pattern].1,
+		};
+
+		let mut tokens: Vec<DigitToken> = if self.ignore_case {
+			// Lowercasing can change the length of a char, so remember which char of `line` every lowercased byte came from
+			let mut lowercase = String::with_capacity(line.len());
+			let mut source_chars = Vec::with_capacity(line.len());
+
+			for (index, ch) in line.char_indices() {
+				for lower in ch.to_lowercase() {
+					lowercase.push(lower);
+
+					for _ in 0..lower.len_utf8() {
+						source_chars.push(index..index + ch.len_utf8());
+					}
+				}
+			}
+
+			self.matcher.find_overlapping(&lowercase)
+				.map(|m| token(m.pattern, source_chars[m.start].start..source_chars[m.end - 1].end))
+				.collect()
+
+		} else {
+			self.matcher.find_overlapping(line)
+				.map(|m| token(m.pattern, m.start..m.end))
+				.collect()
+		};
+
+		// Matches are found in order of where they end
+		tokens.sort_by_key(|token| (token.span.start, token.span.end));
+		tokens
+	}
+
+	/// The first and last digits of `line` as a two digit number, or None if `line` has no digits at all.
+	pub fn calibration_value(&self, line: &str) -> Option<u32> {
+		first_and_last_digits(&self.tokens(line))
+			.map(|value| value as u32)
+	}
+}
+
+
+/// A numeral or word standing for a number, found in a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitToken {
+	/// Byte range of the token within the line.
+	pub span: Range<usize>,
+	pub kind: TokenKind,
+	pub value: u32,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+	Numeral,
+	Word,
+}
+
+impl DigitToken {
+	/// The decimal digits of the token's value, most significant first.
+	pub fn digits(&self) -> impl Iterator<Item=u32> {
+		let value = self.value;
+		let num_digits = value.checked_ilog10().unwrap_or(0) + 1;
+
+		(0..num_digits).rev()
+			.map(move |power| value / 10u32.pow(power) % 10)
+	}
+}
+
+
+/// Sums `reducer` over the tokens of every line, skipping lines it gives None for.
+pub fn sum_lines(lines: &[String], vocabulary: &Vocabulary, reducer: impl Fn(&[DigitToken]) -> Option<u64>) -> u64 {
+	lines.iter()
+		.filter_map(|line| reducer(&vocabulary.tokens(line)))
+		.sum()
+}
+
+// Reducers combine a line's tokens into a single value. Tokens with multi-digit values count as each of their digits.
+// Each gives None if there are no tokens, or if the result doesn't fit in a u64.
+
+/// The puzzle's calibration value: the digit that starts first followed by the digit that ends last.
+pub fn first_and_last_digits(tokens: &[DigitToken]) -> Option<u64> {
+	let first = tokens.first()?;
+	let last = tokens.iter().max_by_key(|token| token.span.end)?;
+
+	Some(u64::from(first.digits().next()? * 10 + last.value % 10))
+}
+
+/// Every digit in the line, in order, read as one number.
+pub fn all_digits(tokens: &[DigitToken]) -> Option<u64> {
+	tokens.iter()
+		.flat_map(DigitToken::digits)
+		.try_fold(None, |number: Option<u64>, digit| {
+			let number = number.unwrap_or(0).checked_mul(10)?.checked_add(u64::from(digit))?;
+			Some(Some(number))
+		})?
+}
+
+/// The sum of every digit in the line.
+pub fn digit_sum(tokens: &[DigitToken]) -> Option<u64> {
+	let mut digits = tokens.iter().flat_map(DigitToken::digits).peekable();
+	digits.peek()?;
+
+	Some(digits.map(u64::from).sum())
+}
+
+/// The first, middle and last digits as a three digit number. With an even number of digits,
+/// the middle is the later of the two central digits.
+pub fn first_middle_last_digits(tokens: &[DigitToken]) -> Option<u64> {
+	let digits: Vec<u32> = tokens.iter().flat_map(DigitToken::digits).collect();
+	let (first, last) = (digits.first()?, digits.last()?);
+	let middle = digits[digits.len() / 2];
+
+	Some(u64::from(first * 100 + middle * 10 + last))
+}
+
+
+/// Both parts' values for every line, to find lines where spelled out digits change the answer.
+#[derive(Debug, Clone)]
+pub struct CalibrationReport {
+	pub lines: Vec<LineReport>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineReport {
+	pub line: String,
+	pub part1: Option<u32>,
+	pub part2: Option<u32>,
+	pub tokens: Vec<DigitToken>,
+}
+
+impl LineReport {
+	/// Whether reading spelled out digits gave a different value.
+	pub fn changed(&self) -> bool {
+		self.part1 != self.part2
+	}
+}
+
+impl CalibrationReport {
+	pub fn new(lines: &[String], vocabulary: &Vocabulary) -> CalibrationReport {
+		let lines = lines.iter()
+			.map(|line| LineReport {
+				line: line.clone(),
+				part1: part1(line),
+				part2: vocabulary.calibration_value(line),
+				tokens: vocabulary.tokens(line),
+			})
+			.collect();
+
+		CalibrationReport { lines }
+	}
+
+	pub fn part1_total(&self) -> u64 {
+		self.lines.iter().filter_map(|line| line.part1).map(u64::from).sum()
+	}
+
+	pub fn part2_total(&self) -> u64 {
+		self.lines.iter().filter_map(|line| line.part2).map(u64::from).sum()
+	}
+}
+
+/// One row per line, marked with `*` where the parts disagree, followed by the totals.
+/// Tokens are written as `<text>@<byte span>`, with `=<value>` after words.
+impl fmt::Display for CalibrationReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let number_width = self.lines.len().to_string().len();
+		let line_width = self.lines.iter().map(|line| line.line.chars().count()).max().unwrap_or(0);
+
+		let value = |value: Option<u32>| value.map_or_else(|| "-".to_owned(), |value| value.to_string());
+
+		for (index, line) in self.lines.iter().enumerate() {
+			let marker = if line.changed() { '*' } else { ' ' };
+
+			write!(f, "{marker} {:>number_width$}  {:<line_width$}  {:>2}  {:>2} ", index + 1, line.line, value(line.part1), value(line.part2))?;
+
+			for token in &line.tokens {
+				write!(f, " {}@{}..{}", &line.line[token.span.clone()], token.span.start, token.span.end)?;
+
+				if token.kind == TokenKind::Word {
+					write!(f, "={}", token.value)?;
+				}
+			}
+
+			writeln!(f)?;
+		}
+
+		let num_changed = self.lines.iter().filter(|line| line.changed()).count();
+		writeln!(f, "part 1 total: {}", self.part1_total())?;
+		writeln!(f, "part 2 total: {}", self.part2_total())?;
+		writeln!(f, "{num_changed} of {} lines changed by spelled out digits", self.lines.len())
+	}
+}
+
+fn part1(s: &str) -> Option<u32> {
+	let first = s.chars().find_map(|c| c.to_digit(10))?;
+	let second = s.chars().rev().find_map(|c| c.to_digit(10))?;
+
+	Some(first * 10 + second)
+}
+
+fn part2(s: &str) -> Option<u32> {
+	Vocabulary::english().calibration_value(s)
+}
+
+
+
+#[test]
+fn test_part1() {
+	let lines: Vec<_> = include_str!("day1.reference.txt").lines().collect();
+
+	assert_eq!(part1(lines[0]), Some(12));
+	assert_eq!(part1(lines[1]), Some(38));
+	assert_eq!(part1(lines[2]), Some(15));
+	assert_eq!(part1(lines[3]), Some(77));
+	assert_eq!(part1("eightwothree"), None);
+
+	assert_eq!(Day1::part1(&Day1::parse(include_str!("day1.reference.txt")).unwrap()), 142);
+}
+
+#[test]
+fn test_part2() {
+	let values: Vec<_> = include_str!("day1.part2.reference.txt").lines()
+		.map(|line| part2(line).unwrap())
+		.collect();
+
+	assert_eq!(values, &[29, 83, 13, 24, 42, 14, 76]);
+
+	// Spelled digits may overlap
+	assert_eq!(part2("eightwo"), Some(82));
+	assert_eq!(part2("oneight"), Some(18));
+	assert_eq!(part2("twone3oneight"), Some(28));
+	assert_eq!(part2("sevenine"), Some(79));
+	assert_eq!(part2("7"), Some(77));
+
+	assert_eq!(Day1::part2(&Day1::parse(include_str!("day1.part2.reference.txt")).unwrap()), 281);
+}
+
+#[test]
+fn test_unicode_and_missing_digits() {
+	assert_eq!(part2("naïve7café"), Some(77));
+	assert_eq!(part2("ünë2twö"), Some(22));
+	assert_eq!(part2("éone→nine€"), Some(19));
+	assert_eq!(part2("日本3語four"), Some(34));
+	assert_eq!(part2("no digits here"), None);
+	assert_eq!(part2(""), None);
+	assert_eq!(part1("ß9ü"), Some(99));
+
+	let error = Day1::parse("1abc2\nñope\nthree").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "ñope"));
+
+	// Part 1 skips lines with only spelled out digits
+	let lines = Day1::parse("1abc2\nthree").unwrap();
+	assert_eq!(Day1::part1(&lines), 12);
+	assert_eq!(Day1::part2(&lines), 12 + 33);
+}
+
+#[test]
+fn test_vocabularies() {
+	let french = Vocabulary::parse("un 1\ndeux 2\ntrois 3\n# comment\n\nzéro 0", false).unwrap();
+	assert_eq!(french.calibration_value("xdeuxtroisy"), Some(23));
+	assert_eq!(french.calibration_value("zérounx"), Some(1));
+	assert_eq!(french.calibration_value("onetwo"), None);
+
+	let german = Vocabulary::new([("eins", 1), ("zwei", 2), ("drei", 3), ("zwölf", 12)], true);
+	assert_eq!(german.calibration_value("ZWEIundDrei"), Some(23));
+	assert_eq!(german.calibration_value("ZWÖLFx"), Some(12));
+	assert_eq!(german.calibration_value("1zwölf"), Some(12));
+	assert_eq!(german.calibration_value("zwölf"), Some(12));
+	assert_eq!(german.tokens("ÄEins")[0].span, 2..6);
+
+	// 'İ' lowercases to two chars, but matches still point into the original line
+	let dotted = Vocabulary::new([("i̇ki", 2)], true);
+	assert_eq!(dotted.tokens("xİKİ")[0].span, 1..6);
+
+	let teens = Vocabulary::new([("thirteen", 13), ("three", 3)], false);
+	assert_eq!(teens.calibration_value("thirteen5"), Some(15));
+	assert_eq!(teens.calibration_value("5thirteen"), Some(53));
+
+	let words = "eins 1\nzwei 2";
+	let lines = parse_with("abeins\nzwei3", &Vocabulary::parse(words, false).unwrap()).unwrap();
+	assert_eq!(part2_with(&lines, &Vocabulary::parse(words, false).unwrap()), 11 + 23);
+	assert!(Day1::parse("abeins").is_err());
+
+	let error = Vocabulary::parse("one 1\ntwo\n", false).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (2, 4, "a value after the word"));
+
+	let error = Vocabulary::parse("one 1\ntwo x", false).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 5, "x"));
+}
+
+#[test]
+fn test_tokens_and_reducers() {
+	let tokens = Vocabulary::english().tokens("xtwone3ñeight");
+
+	assert_eq!(tokens, &[
+		DigitToken { span: 1..4, kind: TokenKind::Word, value: 2 },
+		DigitToken { span: 3..6, kind: TokenKind::Word, value: 1 },
+		DigitToken { span: 6..7, kind: TokenKind::Numeral, value: 3 },
+		DigitToken { span: 9..14, kind: TokenKind::Word, value: 8 },
+	]);
+
+	assert_eq!(first_and_last_digits(&tokens), Some(28));
+	assert_eq!(all_digits(&tokens), Some(2138));
+	assert_eq!(digit_sum(&tokens), Some(14));
+	assert_eq!(first_middle_last_digits(&tokens), Some(238));
+
+	assert_eq!(first_and_last_digits(&[]), None);
+	assert_eq!(all_digits(&[]), None);
+	assert_eq!(digit_sum(&[]), None);
+
+	let teens = Vocabulary::new([("thirteen", 13)], false);
+	let tokens = teens.tokens("thirteen4");
+	assert_eq!(tokens[0].digits().collect::<Vec<_>>(), &[1, 3]);
+	assert_eq!(all_digits(&tokens), Some(134));
+	assert_eq!(digit_sum(&tokens), Some(8));
+
+	let zero = DigitToken { span: 0..1, kind: TokenKind::Numeral, value: 0 };
+	assert_eq!(zero.digits().collect::<Vec<_>>(), &[0]);
+
+	// Too many digits to fit in a u64
+	assert_eq!(all_digits(&Vocabulary::english().tokens(&"9".repeat(25))), None);
+
+	let lines = Day1::parse(include_str!("day1.part2.reference.txt")).unwrap();
+	assert_eq!(sum_lines(&lines, Vocabulary::english(), first_and_last_digits), 281);
+	assert_eq!(sum_lines(&lines, Vocabulary::english(), digit_sum), 102);
+}
+
+#[test]
+fn test_report() {
+	let lines = Day1::parse("two1nine\n7pqrst\nzoneight234\nxtwone3").unwrap();
+	let report = CalibrationReport::new(&lines, Vocabulary::english());
+
+	let changed: Vec<_> = report.lines.iter().map(LineReport::changed).collect();
+	assert_eq!(changed, &[true, false, true, true]);
+	assert_eq!(report.lines[2].part1, Some(24));
+	assert_eq!(report.lines[2].part2, Some(14));
+	assert_eq!(report.part1_total(), 11 + 77 + 24 + 33);
+	assert_eq!(report.part2_total(), 29 + 77 + 14 + 23);
+
+	assert_eq!(report.to_string(), String::new()
+		+ "* 1  two1nine     11  29  two@0..3=2 1@3..4 nine@4..8=9\n"
+		+ "  2  7pqrst       77  77  7@0..1\n"
+		+ "* 3  zoneight234  24  14  one@1..4=1 eight@3..8=8 2@8..9 3@9..10 4@10..11\n"
+		+ "* 4  xtwone3      33  23  two@1..4=2 one@3..6=1 3@6..7\n"
+		+ "part 1 total: 145\n"
+		+ "part 2 total: 143\n"
+		+ "3 of 4 lines changed by spelled out digits\n");
+
+	// Lines without numerals have no part 1 value
+	let lines = Day1::parse("eightwo").unwrap();
+	let report = CalibrationReport::new(&lines, Vocabulary::english());
+	assert!(report.to_string().starts_with("* 1  eightwo   -  82  eight@0..5=8 two@4..7=2\n"));
+}
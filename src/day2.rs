@@ -0,0 +1,154 @@
+use crate::{Solution, ParseError};
+use crate::parse::{parse_lines, parse_number, split_once};
+
+
+pub struct Day2;
+
+impl Solution for Day2 {
+	type Parsed = Vec<Game>;
+	type Part1 = u64;
+	/// Cube counts are `u32`, so a power can take up to 96 bits.
+	type Part2 = u128;
+
+	fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+		parse_lines(input, to_game)
+	}
+
+	fn part1(games: &Vec<Game>) -> u64 {
+		games.iter()
+			.filter(|Game{cubes, ..}| {
+				cubes.red <= 12
+				&& cubes.green <= 13
+				&& cubes.blue <= 14
+			})
+			.map(|game| u64::from(game.id))
+			.sum()
+	}
+
+	fn part2(games: &Vec<Game>) -> u128 {
+		games.iter()
+			.map(|Game{cubes, ..}| {
+				u128::from(cubes.red) * u128::from(cubes.green) * u128::from(cubes.blue)
+			})
+			.sum()
+	}
+}
+
+
+#[derive(Default, Debug)]
+struct Hand {
+	red: u32,
+	green: u32,
+	blue: u32,
+}
+
+#[derive(Debug)]
+pub struct Game {
+	id: u32,
+	cubes: Hand,
+}
+
+fn to_game(line: &str) -> Result<Game, ParseError> {
+	let (id_str, cubes_str) = split_once(line, line, ':', "':' after the game id")?;
+
+	let id_str = id_str.strip_prefix("Game ")
+		.ok_or_else(|| ParseError::new(line, id_str, "'Game <id>'"))?;
+
+	let id = parse_number(line, id_str)?;
+	let mut cubes = Hand::default();
+
+	for hand_str in cubes_str.split(';') {
+		let hand = to_hand(line, hand_str)?;
+
+		cubes = Hand {
+			red: cubes.red.max(hand.red),
+			green: cubes.green.max(hand.green),
+			blue: cubes.blue.max(hand.blue),
+		};
+	}
+
+	Ok(Game { id, cubes })
+}
+
+/// `s` must be a subslice of `line`, so that errors can be located.
+fn to_hand(line: &str, s: &str) -> Result<Hand, ParseError> {
+	let mut hand = Hand::default();
+
+	for cubes_str in s.split(',').map(str::trim) {
+		let count_and_colour = cubes_str.split_once(' ')
+			.ok_or_else(|| ParseError::new(line, cubes_str, "'<count> <colour>'"))?;
+
+		match count_and_colour {
+			(n, "red") => hand.red = parse_number(line, n)?,
+			(n, "green") => hand.green = parse_number(line, n)?,
+			(n, "blue") => hand.blue = parse_number(line, n)?,
+			(_, colour) => return Err(ParseError::new(line, colour, "red, green or blue")),
+		}
+	}
+
+	Ok(hand)
+}
+
+
+
+#[test]
+fn test_to_hand() {
+	let hand = to_hand(" 3 blue, 4 red", " 3 blue, 4 red").unwrap();
+	assert_eq!((hand.red, hand.green, hand.blue), (4, 0, 3));
+
+	let hand = to_hand(" 1 red, 2 green, 6 blue", " 1 red, 2 green, 6 blue").unwrap();
+	assert_eq!((hand.red, hand.green, hand.blue), (1, 2, 6));
+
+	let hand = to_hand(" 2 green", " 2 green").unwrap();
+	assert_eq!((hand.red, hand.green, hand.blue), (0, 2, 0));
+}
+
+#[test]
+fn test_to_game() {
+	let games: Vec<_> = include_str!("day2.reference.txt").lines()
+		.map(|line| to_game(line).unwrap())
+		.collect();
+
+	assert_eq!(games.len(), 5);
+
+	let Game{id, cubes} = &games[0];
+	assert_eq!(*id, 1);
+	assert_eq!((cubes.red, cubes.green, cubes.blue), (4, 2, 6));
+
+	let Game{id, cubes} = &games[2];
+	assert_eq!(*id, 3);
+	assert_eq!((cubes.red, cubes.green, cubes.blue), (20, 13, 6));
+}
+
+#[test]
+fn test_parts() {
+	let games = Day2::parse(include_str!("day2.reference.txt")).unwrap();
+
+	assert_eq!(Day2::part1(&games), 8);
+	assert_eq!(Day2::part2(&games), 2286);
+
+	// The power of a set doesn't fit in a u32, or even a u64
+	let games = Day2::parse("Game 1: 5000 red, 5000 green, 5000 blue").unwrap();
+	assert_eq!(Day2::part2(&games), 125_000_000_000);
+
+	let games = Day2::parse("Game 1: 4000000000 red, 4000000000 green, 4000000000 blue").unwrap();
+	assert_eq!(Day2::part2(&games), 64_000_000_000_000_000_000_000_000_000);
+
+	let games = Day2::parse("Game 1: 3000000 red, 3000000 green, 3000000 blue\nGame 2: 3000000 red, 3000000 green, 3000000 blue").unwrap();
+	assert_eq!(Day2::part2(&games), 54_000_000_000_000_000_000);
+}
+
+#[test]
+fn test_parse_errors() {
+	let error = to_game("Game 1: 3 blue, 4 purple").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str(), error.expected), (1, 19, "purple", "red, green or blue"));
+
+	let error = to_game("Game x: 3 blue").unwrap_err();
+	assert_eq!((error.column, error.text.as_str()), (6, "x"));
+
+	let error = to_game("Game 1 3 blue").unwrap_err();
+	assert_eq!(error.expected, "':' after the game id");
+
+	let error = Day2::parse("Game 1: 3 blue\nGame 2: 3blue").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 9, "3blue"));
+}
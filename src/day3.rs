@@ -0,0 +1,195 @@
+use common::*;
+
+use crate::{Solution, ParseError};
+use crate::grid::{Grid, Run, Bounds, cmp_vec2i};
+use crate::parse::parse_number;
+
+
+pub struct Day3;
+
+impl Solution for Day3 {
+	type Parsed = Map;
+	type Part1 = u64;
+	/// Each gear ratio fits in a u64, and every gear needs its own '*', so the sum always fits in a u128.
+	type Part2 = u128;
+
+	fn parse(input: &str) -> Result<Map, ParseError> {
+		parse_map(input)
+	}
+
+	fn part1(map: &Map) -> u64 {
+		map.part_numbers.iter()
+			.map(|n| u64::from(n.value))
+			.sum()
+	}
+
+	fn part2(map: &Map) -> u128 {
+		map.symbols.iter()
+			.filter(|sym| sym.ch == '*')
+			.filter_map(|sym| get_exactly_two_adjacent_values(sym.pos, &map.part_numbers))
+			.map(|(a, b)| u128::from(a) * u128::from(b)) // gear ratio
+			.sum()
+	}
+}
+
+
+fn parse_map(document: &str) -> Result<Map, ParseError> {
+	let grid = Grid::parse_with(document, "a digit, '.' or a symbol", |ch| {
+		(ch.is_ascii_digit() || ch.is_ascii_punctuation()).then_some(ch)
+	})?;
+
+	let mut map = Map::default();
+
+	// Find symbols
+	map.symbols = grid.iter()
+		.filter(|(_, &ch)| ch != '.' && ch.is_ascii_punctuation())
+		.map(|(pos, &ch)| Symbol { pos, ch })
+		.collect();
+
+	// Find numbers
+	// Only ascii is allowed, so x coordinates are also byte indices into each line
+	let lines: Vec<&str> = document.lines().collect();
+
+	for run in grid.row_runs(char::is_ascii_digit) {
+		let y = run.start.y as usize;
+		let start = run.start.x as usize;
+		let number_str = &lines[y][start..start + run.length as usize];
+
+		let value = parse_number(lines[y], number_str)
+			.map_err(|error| error.offset_lines(y))?;
+
+		let number = Number {
+			pos: run.start,
+			value,
+			span: run.length,
+		};
+
+		if number_has_adjacent_symbol(&number, &map.symbols) {
+			map.part_numbers.push(number);
+		}
+	}
+
+	Ok(map)
+}
+
+
+fn get_exactly_two_adjacent_values(pos: Vec2i, numbers: &[Number]) -> Option<(u32, u32)> {
+	let numbers = {
+		let start_index = numbers.partition_point(|num| num.pos.y < pos.y - 1);
+		let end_index = numbers[start_index..].partition_point(|num| num.pos.y <= pos.y + 1);
+		&numbers[start_index..start_index + end_index]
+	};
+
+	let mut it = numbers.iter().filter(|num| num.bounds().contains(pos)).map(|num| num.value);
+	let result = (it.next()?, it.next()?);
+
+	if it.next().is_none() {
+		Some(result)
+	} else {
+		None
+	}
+}
+
+fn number_has_adjacent_symbol(number: &Number, symbols: &[Symbol]) -> bool {
+	let bounds = number.bounds();
+
+	let symbol_scope = {
+		let start_index = symbols.partition_point(|sym| cmp_vec2i(sym.pos, bounds.min).is_lt());
+		let end_index = symbols[start_index..].partition_point(|sym| cmp_vec2i(sym.pos, bounds.max).is_le());
+		&symbols[start_index..start_index + end_index]
+	};
+
+	symbol_scope.iter()
+		.any(move |sym| bounds.contains(sym.pos))
+}
+
+
+
+#[derive(Debug, Default)]
+pub struct Map {
+	symbols: Vec<Symbol>,
+	part_numbers: Vec<Number>,
+}
+
+#[derive(Debug)]
+struct Number {
+	pos: Vec2i,
+	span: i32,
+
+	value: u32,
+}
+
+impl Number {
+	fn bounds(&self) -> Bounds {
+		Run { start: self.pos, length: self.span }
+			.surrounding_bounds()
+	}
+}
+
+#[derive(Debug)]
+struct Symbol {
+	pos: Vec2i,
+	ch: char,
+}
+
+
+
+
+#[test]
+fn test_parse_map() {
+	let map = parse_map(include_str!("day3.reference.txt")).unwrap();
+
+	let symbols: Vec<_> = map.symbols.iter().map(|sym| (sym.pos, sym.ch)).collect();
+	assert_eq!(symbols, &[
+		(Vec2i::new(3, 1), '*'),
+		(Vec2i::new(6, 3), '#'),
+		(Vec2i::new(3, 4), '*'),
+		(Vec2i::new(5, 5), '+'),
+		(Vec2i::new(3, 8), '$'),
+		(Vec2i::new(5, 8), '*'),
+	]);
+
+	// 114 and 58 aren't adjacent to any symbol
+	let part_numbers: Vec<_> = map.part_numbers.iter().map(|num| num.value).collect();
+	assert_eq!(part_numbers, &[467, 35, 633, 617, 592, 755, 664, 598]);
+
+	assert_eq!(map.part_numbers[0].pos, Vec2i::new(0, 0));
+	assert_eq!(map.part_numbers[0].span, 3);
+}
+
+#[test]
+fn test_get_exactly_two_adjacent_values() {
+	let map = parse_map(include_str!("day3.reference.txt")).unwrap();
+
+	assert_eq!(get_exactly_two_adjacent_values(Vec2i::new(3, 1), &map.part_numbers), Some((467, 35)));
+	assert_eq!(get_exactly_two_adjacent_values(Vec2i::new(5, 8), &map.part_numbers), Some((755, 598)));
+
+	// Only adjacent to 617
+	assert_eq!(get_exactly_two_adjacent_values(Vec2i::new(3, 4), &map.part_numbers), None);
+}
+
+#[test]
+fn test_parts() {
+	let map = Day3::parse(include_str!("day3.reference.txt")).unwrap();
+
+	assert_eq!(Day3::part1(&map), 4361);
+	assert_eq!(Day3::part2(&map), 467835);
+
+	// Neither answer fits in a u32
+	let map = Day3::parse("4000000000.\n*..........\n100000*1000").unwrap();
+	assert_eq!(Day3::part1(&map), 4_000_101_000);
+	assert_eq!(Day3::part2(&map), 400_000_100_000_000);
+
+	// Two ratios near u64::MAX add up to more than it
+	let map = Day3::parse("4294967295*4294967295\n.....................\n4294967295*4294967295").unwrap();
+	assert_eq!(Day3::part2(&map), 2 * 4294967295u128 * 4294967295);
+}
+
+#[test]
+fn test_parse_errors() {
+	let error = parse_map("467..114..\n...*..x...").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 7, "x"));
+
+	let error = parse_map("..99999999999..\n...*...........").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str(), error.expected), (1, 3, "99999999999", "a number"));
+}
@@ -0,0 +1,192 @@
+use crate::{Solution, ParseError};
+use crate::parse::{parse_lines, parse_number, split_once};
+
+
+pub struct Day4;
+
+impl Solution for Day4 {
+	type Parsed = Vec<Card>;
+	type Part1 = u64;
+	type Part2 = usize;
+
+	fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+		let cards = parse_lines(input, to_card)?;
+
+		// Both totals can grow past what their types can hold, so check them before either part runs.
+		// Each card is a line, so the card that overflows is also the line to point at
+		let overflow = total_score(&cards).err()
+			.map(|index| (index, "a card that doesn't take the total score past u64::MAX"))
+			.or_else(|| evaluate_total_scratchcards(&cards).err()
+				.map(|index| (index, "a card that doesn't take the number of scratchcards past usize::MAX")));
+
+		if let Some((index, expected)) = overflow {
+			return Err(ParseError::new(input, input.lines().nth(index).unwrap(), expected))
+		}
+
+		Ok(cards)
+	}
+
+	fn part1(cards: &Vec<Card>) -> u64 {
+		total_score(cards).expect("total score checked while parsing")
+	}
+
+	fn part2(cards: &Vec<Card>) -> usize {
+		evaluate_total_scratchcards(cards).expect("number of scratchcards checked while parsing")
+	}
+}
+
+
+#[derive(Debug)]
+pub struct Card {
+	winning: Vec<u32>,
+	have: Vec<u32>,
+}
+
+fn to_card(line: &str) -> Result<Card, ParseError> {
+	let numbers_str = split_once(line, line, ':', "':' after the card id")?.1;
+	let (winning_str, have_str) = split_once(line, numbers_str, '|', "'|' between winning numbers and numbers you have")?;
+	let mut winning: Vec<_> = winning_str.split_whitespace()
+		.map(|s| parse_number(line, s))
+		.collect::<Result<_, _>>()?;
+
+	let mut have: Vec<_> = have_str.split_whitespace()
+		.map(|s| parse_number(line, s))
+		.collect::<Result<_, _>>()?;
+
+	winning.sort();
+	have.sort();
+
+	let card = Card { winning, have };
+
+	// Scores double with every match, so more than this wouldn't fit in the score
+	if calculate_num_matches(&card) > u64::BITS {
+		return Err(ParseError::new(line, have_str, "no more than 64 numbers matching the winning numbers"))
+	}
+
+	Ok(card)
+}
+
+fn calculate_num_matches(card: &Card) -> u32 {
+	card.have.iter()
+		.filter(|have| card.winning.binary_search(have).is_ok())
+		.count() as u32
+}
+
+fn card_score(card: &Card) -> u64 {
+	match calculate_num_matches(card) {
+		0 => 0,
+		num_matches => 1 << (num_matches - 1),
+	}
+}
+
+/// The sum of every card's score, or the index of the card whose score takes it past `u64::MAX`.
+fn total_score(cards: &[Card]) -> Result<u64, usize> {
+	cards.iter()
+		.enumerate()
+		.try_fold(0u64, |total, (index, card)| total.checked_add(card_score(card)).ok_or(index))
+}
+
+/// The number of scratchcards once every copy has been won,
+/// or the index of the first card whose copies take a count past `usize::MAX`.
+fn evaluate_total_scratchcards(cards: &[Card]) -> Result<usize, usize> {
+	let mut num_card_copies: Vec<usize> = vec![1; cards.len()];
+
+	for (index, card) in cards.iter().enumerate() {
+		let matched_card_copies = num_card_copies[index];
+		let num_matches = calculate_num_matches(card);
+
+		// Cards never win copies of cards past the end of the table
+		let copies_end = (index + num_matches as usize + 1).min(cards.len());
+
+		for (offset, successive_card_copies) in num_card_copies[index+1 .. copies_end].iter_mut().enumerate() {
+			*successive_card_copies = successive_card_copies.checked_add(matched_card_copies).ok_or(index + 1 + offset)?;
+		}
+	}
+
+	num_card_copies.into_iter()
+		.enumerate()
+		.try_fold(0usize, |total, (index, copies)| total.checked_add(copies).ok_or(index))
+}
+
+
+#[test]
+fn test_to_card() {
+	let card = to_card("Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53").unwrap();
+
+	assert_eq!(card.winning, &[17, 41, 48, 83, 86]);
+	assert_eq!(card.have, &[6, 9, 17, 31, 48, 53, 83, 86]);
+}
+
+#[test]
+fn test_card_score() {
+	let scores: Vec<_> = include_str!("day4.reference.txt").lines()
+		.map(|line| to_card(line).unwrap())
+		.map(|card| card_score(&card))
+		.collect();
+
+	assert_eq!(scores, &[8, 2, 2, 1, 0, 0]);
+}
+
+#[test]
+fn test_evaluate_total_scratchcards() {
+	let cards = Day4::parse(include_str!("day4.reference.txt")).unwrap();
+
+	assert_eq!(evaluate_total_scratchcards(&cards), Ok(30));
+	assert_eq!(evaluate_total_scratchcards(&cards[4..]), Ok(2));
+}
+
+#[test]
+fn test_parts() {
+	let cards = Day4::parse(include_str!("day4.reference.txt")).unwrap();
+
+	assert_eq!(Day4::part1(&cards), 13);
+	assert_eq!(Day4::part2(&cards), 30);
+}
+
+#[test]
+fn test_parse_errors() {
+	let error = to_card("Card 1: 41 48 | 83 8x6").unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (1, 20, "8x6"));
+
+	let error = to_card("Card 1: 41 48 83 86").unwrap_err();
+	assert_eq!((error.column, error.text.as_str()), (20, ""));
+
+	let error = Day4::parse("Card 1: 1 | 2\nCard 2 1 | 2").unwrap_err();
+	assert_eq!((error.line, error.expected), (2, "':' after the card id"));
+
+	let too_many_matches = format!("Card 1: 1 | {}", "1 ".repeat(65));
+	let error = to_card(&too_many_matches).unwrap_err();
+	assert_eq!((error.line, error.column), (1, 12));
+
+	let most_matches = format!("Card 1: 1 | {}", "1 ".repeat(64));
+	assert_eq!(card_score(&to_card(&most_matches).unwrap()), 1 << 63);
+}
+
+#[test]
+fn test_wins_past_the_end() {
+	let cards = Day4::parse("Card 1: 1 2 | 1 2").unwrap();
+	assert_eq!(Day4::part2(&cards), 1);
+
+	let cards = Day4::parse("Card 1: 1 2 | 1 2\nCard 2: 3 4 | 3 4 5").unwrap();
+	assert_eq!(Day4::part1(&cards), 4);
+	assert_eq!(Day4::part2(&cards), 3);
+}
+
+#[test]
+fn test_overflowing_totals() {
+	// Each card scores 2^63, so the second takes the total past u64::MAX
+	let most_matches = format!("Card 1: 1 | {}\n", "1 ".repeat(64));
+	let error = Day4::parse(&most_matches.repeat(2)).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (2, 1, "a card that doesn't take the total score past u64::MAX"));
+
+	let cards = Day4::parse(&most_matches).unwrap();
+	assert_eq!(Day4::part1(&cards), 1 << 63);
+
+	// Every card wins a copy of each of the next ten, so the number of copies grows exponentially
+	let ten_matches = "Card 1: 1 2 3 4 5 6 7 8 9 10 | 1 2 3 4 5 6 7 8 9 10\n";
+	let error = Day4::parse(&ten_matches.repeat(100)).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (66, 1, "a card that doesn't take the number of scratchcards past usize::MAX"));
+
+	let cards = Day4::parse(&ten_matches.repeat(20)).unwrap();
+	assert_eq!(Day4::part2(&cards), 1_044_478);
+}
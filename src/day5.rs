@@ -0,0 +1,1640 @@
+use crate::{Solution, ParseError};
+use crate::interval::{Interval, IntervalSet, DOMAIN_END};
+use crate::parse::{line_offset, parse_number};
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+#[cfg(test)]
+use crate::rng::Rng;
+
+
+pub struct Day5;
+
+impl Solution for Day5 {
+	type Parsed = Puzzle;
+	type Part1 = usize;
+	type Part2 = usize;
+
+	fn parse(input: &str) -> Result<Puzzle, ParseError> {
+		let almanac = Almanac::parse(input)?;
+
+		// Both parts look for the lowest location of a seed, so there has to be one either way the seeds are read
+		let seeds_str = input.lines().next().unwrap_or(input);
+
+		if almanac.seeds.is_empty() {
+			return Err(ParseError::new(input, &seeds_str[seeds_str.len()..], "at least one seed"))
+		}
+
+		if almanac.seed_ranges.iter().all(Interval::is_empty) {
+			return Err(ParseError::new(input, &seeds_str[seeds_str.len()..], "at least one seed range with a non-zero length"))
+		}
+
+		let seed_to_location = almanac.composed_map("seed", "location")
+			.ok_or_else(|| ParseError::at_end(input, "maps leading from seed to location"))?;
+
+		Ok(Puzzle { almanac, seed_to_location })
+	}
+
+	fn part1(puzzle: &Puzzle) -> usize {
+		puzzle.almanac.seeds.iter()
+			.map(|&seed| puzzle.seed_to_location.lookup(seed))
+			.min()
+			.unwrap()
+	}
+
+	fn part2(puzzle: &Puzzle) -> usize {
+		puzzle.almanac.seed_ranges.iter()
+			.flat_map(|&range| puzzle.seed_to_location.map_range_to_destination_ranges(range))
+			.map(|range| range.start())
+			.min()
+			.unwrap()
+	}
+
+	fn warnings(puzzle: &Puzzle) -> Vec<String> {
+		puzzle.almanac.issues().iter()
+			.map(MapIssue::to_string)
+			.collect()
+	}
+}
+
+
+/// An almanac, along with every map from seed to location composed into one so each part is a single lookup.
+#[derive(Debug)]
+pub struct Puzzle {
+	pub almanac: Almanac,
+	pub seed_to_location: Map,
+}
+
+
+/// The numbers of a single map entry, as written.
+#[derive(Debug, Copy, Clone)]
+struct RawEntry {
+	destination_start: usize,
+	source_start: usize,
+	length: usize,
+}
+
+fn parse_entry(entry_str: &str) -> Result<RawEntry, ParseError> {
+	let mut number_it = entry_str.split_whitespace();
+
+	let mut next_number = |expected| {
+		let number_str = number_it.next()
+			.ok_or_else(|| ParseError::at_end(entry_str, expected))?;
+
+		parse_number(entry_str, number_str)
+	};
+
+	let destination_start = next_number("a destination start")?;
+	let source_start = next_number("a source start")?;
+	let length = next_number("a range length")?;
+
+	// The length is checked against both starts, so every value in either range fits in a usize
+	if MapEntry::new(destination_start, source_start, length).is_none() {
+		let length_str = entry_str.split_whitespace().nth(2).unwrap();
+		return Err(ParseError::new(entry_str, length_str, "a range length that doesn't go past usize::MAX from either start"))
+	}
+
+	if let Some(extra_str) = number_it.next() {
+		return Err(ParseError::new(entry_str, extra_str, "end of line"))
+	}
+
+	Ok(RawEntry {
+		destination_start,
+		source_start,
+		length,
+	})
+}
+
+/// Parses a map section, along with any issues found in its entries. Issue lines are relative to `map_str`.
+fn parse_map(map_str: &str) -> Result<(Map, Vec<MapIssue>), ParseError> {
+	let name_str = map_str.lines().next().unwrap_or(map_str);
+
+	let (source, destination) = name_str.strip_suffix(" map:")
+		.and_then(|name| name.split_once("-to-"))
+		.filter(|(source, destination)| !source.is_empty() && !destination.is_empty())
+		.ok_or_else(|| ParseError::new(map_str, name_str, "'<source>-to-<destination> map:'"))?;
+
+	let raw_entries = map_str.lines()
+		.enumerate()
+		.skip(1) // Skip the name of the map
+		.map(|(index, entry_str)| {
+			let entry = parse_entry(entry_str).map_err(|error| error.offset_lines(index))?;
+			Ok((entry, index + 1))
+		})
+		.collect::<Result<Vec<_>, ParseError>>()?;
+
+	let issues = validate_entries(&raw_entries);
+
+	let entries: Vec<_> = raw_entries.into_iter()
+		.filter(|(entry, _)| entry.length > 0)
+		.map(|(entry, _)| MapEntry::new(entry.destination_start, entry.source_start, entry.length).unwrap())
+		.collect();
+
+	Ok((Map::new(source, destination, entries), issues))
+}
+
+/// Checks for entries that `Map` can't handle correctly, given each entry and the line it came from.
+fn validate_entries(entries: &[(RawEntry, usize)]) -> Vec<MapIssue> {
+	let mut issues = Vec::new();
+	let mut valid_entries = Vec::new();
+
+	for &(entry, line) in entries {
+		if entry.length == 0 {
+			issues.push(MapIssue { line, kind: MapIssueKind::ZeroLength });
+		} else {
+			valid_entries.push((entry, line));
+		}
+	}
+
+	// Sweep through entries in order of start, remembering the entry that reaches furthest so far
+	let mut find_overlaps = |start_of: fn(&RawEntry) -> usize, kind: fn(usize) -> MapIssueKind| {
+		valid_entries.sort_by_key(|(entry, line)| (start_of(entry), *line));
+
+		let mut furthest: Option<(u128, usize)> = None;
+
+		for &(entry, line) in &valid_entries {
+			let start = start_of(&entry) as u128;
+			let end = start + entry.length as u128;
+
+			match furthest {
+				Some((furthest_end, furthest_line)) if start < furthest_end => {
+					issues.push(MapIssue { line, kind: kind(furthest_line) });
+
+					if end > furthest_end {
+						furthest = Some((end, line));
+					}
+				}
+
+				Some((furthest_end, _)) if end <= furthest_end => {}
+				_ => furthest = Some((end, line)),
+			}
+		}
+	};
+
+	find_overlaps(|entry| entry.source_start, |other_line| MapIssueKind::OverlappingSources { other_line });
+	find_overlaps(|entry| entry.destination_start, |other_line| MapIssueKind::CollidingDestinations { other_line });
+
+	issues.sort_by_key(|issue| issue.line);
+	issues
+}
+
+
+/// Something wrong with a map entry, found while parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapIssue {
+	/// 1-based line number of the offending entry.
+	pub line: usize,
+	pub kind: MapIssueKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapIssueKind {
+	/// The entry's source range overlaps the entry on `other_line`, so lookups will only use one of them.
+	OverlappingSources { other_line: usize },
+	/// The entry maps into the same destination range as the entry on `other_line`.
+	CollidingDestinations { other_line: usize },
+	/// The entry has a length of zero, and so maps nothing.
+	ZeroLength,
+}
+
+impl MapIssue {
+	fn offset_lines(mut self, lines: usize) -> MapIssue {
+		self.line += lines;
+
+		match &mut self.kind {
+			MapIssueKind::OverlappingSources { other_line }
+			| MapIssueKind::CollidingDestinations { other_line } => *other_line += lines,
+			_ => {}
+		}
+
+		self
+	}
+
+	/// What the entry should have been instead, for reporting issues as `ParseError`s.
+	fn expected(&self) -> &'static str {
+		match self.kind {
+			MapIssueKind::OverlappingSources{..} => "an entry whose source range doesn't overlap another entry",
+			MapIssueKind::CollidingDestinations{..} => "an entry whose destination range doesn't overlap another entry",
+			MapIssueKind::ZeroLength => "an entry with a non-zero length",
+		}
+	}
+}
+
+impl fmt::Display for MapIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let line = self.line;
+
+		match self.kind {
+			MapIssueKind::OverlappingSources { other_line } => write!(f, "line {line}: source range overlaps the entry on line {other_line}"),
+			MapIssueKind::CollidingDestinations { other_line } => write!(f, "line {line}: destination range overlaps the entry on line {other_line}"),
+			MapIssueKind::ZeroLength => write!(f, "line {line}: entry has zero length"),
+		}
+	}
+}
+
+
+/// How `Almanac::parse_with` treats issues found in map entries.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Validation {
+	/// The first issue is returned as a `ParseError`.
+	Strict,
+	/// Issues are collected and can be read back from `Almanac::issues`.
+	Warn,
+}
+
+
+
+
+#[derive(Debug)]
+pub struct Almanac {
+	seeds: Vec<usize>,
+	seed_ranges: Vec<Interval>,
+
+	/// Every map, in the order they were defined.
+	maps: Vec<Map>,
+
+	/// Indices into `maps`, keyed by the category each map maps from.
+	maps_by_source: HashMap<String, Vec<usize>>,
+
+	issues: Vec<MapIssue>,
+}
+
+impl Almanac {
+	/// Builds an almanac from seed ranges and maps, as if it had been parsed.
+	/// Returns None if two maps share the same source and destination,
+	/// or if a seed range covers every value, so its length doesn't fit in a usize.
+	pub fn new(seed_ranges: impl IntoIterator<Item=Interval>, maps: impl IntoIterator<Item=Map>) -> Option<Almanac> {
+		let mut seed_ranges: Vec<Interval> = seed_ranges.into_iter().collect();
+		let mut seeds: Vec<usize> = seed_ranges.iter()
+			.map(|range| Some([range.start(), usize::try_from(range.len()).ok()?]))
+			.collect::<Option<Vec<_>>>()?
+			.concat();
+
+		seeds.sort();
+		seed_ranges.sort();
+
+		let mut almanac = Almanac {
+			seeds,
+			seed_ranges,
+			maps: Vec::new(),
+			maps_by_source: HashMap::new(),
+			issues: Vec::new(),
+		};
+
+		for map in maps {
+			if !almanac.insert_map(map) {
+				return None
+			}
+		}
+
+		Some(almanac)
+	}
+
+	pub fn parse(almanac_str: &str) -> Result<Almanac, ParseError> {
+		Almanac::parse_with(almanac_str, Validation::Warn)
+	}
+
+	pub fn parse_with(almanac_str: &str, validation: Validation) -> Result<Almanac, ParseError> {
+		let almanac_str = almanac_str.replace("\r\n", "\n");
+		let almanac_str = almanac_str.as_str();
+
+		// Sections are split by empty newlines
+		let mut section_it = almanac_str.split("\n\n").map(str::trim);
+
+		let seeds_section = section_it.next().unwrap_or(almanac_str);
+		// The list of seeds can be empty, which is how an almanac without seeds is written out
+		let seed_str = seeds_section.strip_prefix("seeds:")
+			.ok_or_else(|| ParseError::new(almanac_str, seeds_section, "'seeds: <ids>'"))?;
+
+		let seed_strs: Vec<&str> = seed_str.split_whitespace().collect();
+
+		let mut seeds = seed_strs.iter()
+			.map(|s| parse_number(almanac_str, s))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let mut seed_ranges = seeds.chunks(2)
+			.zip(seed_strs.chunks(2))
+			.map(|(chunk, chunk_strs)| match *chunk {
+				[start, length] => Interval::checked_from_start_length(start, length)
+					.ok_or_else(|| ParseError::new(almanac_str, chunk_strs[1], "a seed range length that doesn't go past usize::MAX")),
+				_ => Err(ParseError::new(almanac_str, &seed_str[seed_str.len()..], "a seed range length")),
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		seeds.sort();
+		seed_ranges.sort();
+
+		let mut almanac = Almanac {
+			seeds,
+			seed_ranges,
+			maps: Vec::new(),
+			maps_by_source: HashMap::new(),
+			issues: Vec::new(),
+		};
+
+		for map_str in section_it.filter(|map_str| !map_str.is_empty()) {
+			let map_line_offset = line_offset(almanac_str, map_str);
+
+			let (map, map_issues) = parse_map(map_str)
+				.map_err(|error| error.offset_lines(map_line_offset))?;
+
+			almanac.issues.extend(map_issues.into_iter().map(|issue| issue.offset_lines(map_line_offset)));
+
+			if let (Validation::Strict, Some(issue)) = (validation, almanac.issues.first()) {
+				let entry_str = almanac_str.lines().nth(issue.line - 1).unwrap();
+				return Err(ParseError::new(almanac_str, entry_str, issue.expected()))
+			}
+
+			if !almanac.insert_map(map) {
+				let name_str = map_str.lines().next().unwrap();
+				return Err(ParseError::new(almanac_str, name_str, "a map that hasn't already been defined"))
+			}
+		}
+
+		Ok(almanac)
+	}
+
+	/// Adds `map` unless there's already a map between the same categories.
+	fn insert_map(&mut self, map: Map) -> bool {
+		if self.map(&map.source, &map.destination).is_some() {
+			return false
+		}
+
+		self.maps_by_source.entry(map.source.clone()).or_default().push(self.maps.len());
+		self.maps.push(map);
+		true
+	}
+
+	/// Every map leading out of `source`.
+	fn maps_from(&self, source: &str) -> impl Iterator<Item=&Map> + '_ {
+		self.maps_by_source.get(source).into_iter().flatten()
+			.map(|&index| &self.maps[index])
+	}
+
+	/// Issues found in map entries while parsing.
+	pub fn issues(&self) -> &[MapIssue] {
+		&self.issues
+	}
+
+	/// The seeds read as `<start> <length>` pairs, sorted by start.
+	pub fn seed_ranges(&self) -> &[Interval] {
+		&self.seed_ranges
+	}
+
+	/// The map directly from `source` to `destination`, if there is one.
+	pub fn map(&self, source: &str, destination: &str) -> Option<&Map> {
+		self.maps_from(source)
+			.find(|map| map.destination == destination)
+	}
+
+	/// The shortest chain of maps leading from `source` to `destination`.
+	/// Empty if `source` and `destination` are the same category.
+	pub fn path(&self, source: &str, destination: &str) -> Option<Vec<&Map>> {
+		// Breadth first search, remembering which map first reached each category
+		let mut reached_by: HashMap<&str, &Map> = HashMap::new();
+		let mut queue = VecDeque::from([source]);
+
+		while let Some(category) = queue.pop_front() {
+			if category == destination {
+				break
+			}
+
+			for map in self.maps_from(category) {
+				if map.destination != source && !reached_by.contains_key(map.destination.as_str()) {
+					reached_by.insert(&map.destination, map);
+					queue.push_back(&map.destination);
+				}
+			}
+		}
+
+		// Walk back from the destination
+		let mut path = Vec::new();
+		let mut category = destination;
+
+		while category != source {
+			let map = reached_by.get(category)?;
+			path.push(*map);
+			category = &map.source;
+		}
+
+		path.reverse();
+		Some(path)
+	}
+
+	pub fn lookup(&self, source: &str, destination: &str, value: usize) -> Option<usize> {
+		let path = self.path(source, destination)?;
+
+		Some(path.into_iter()
+			.fold(value, |value, map| map.lookup(value)))
+	}
+
+	pub fn map_ranges(&self, source: &str, destination: &str, ranges: impl IntoIterator<Item=Interval>) -> Option<impl Iterator<Item=Interval>> {
+		let path = self.path(source, destination)?;
+
+		let ranges = path.into_iter()
+			.fold(ranges.into_iter().collect::<Vec<_>>(), |ranges, map| {
+				ranges.into_iter()
+					.flat_map(|range| map.map_range_to_destination_ranges(range))
+					.collect()
+			});
+
+		Some(ranges.into_iter())
+	}
+
+	/// Like `map_ranges`, but sorts and merges the ranges after every map so later maps see as few ranges as possible.
+	pub fn map_range_set(&self, source: &str, destination: &str, ranges: impl IntoIterator<Item=Interval>) -> Option<IntervalSet> {
+		let stages = self.range_stages(source, destination, ranges)?;
+		Some(stages.into_iter().last().unwrap().ranges)
+	}
+
+	/// The merged ranges in every category from `source` to `destination`, starting with `source` itself.
+	pub fn range_stages(&self, source: &str, destination: &str, ranges: impl IntoIterator<Item=Interval>) -> Option<Vec<RangeStage>> {
+		let path = self.path(source, destination)?;
+
+		let ranges: Vec<Interval> = ranges.into_iter().collect();
+		let mut stages = vec![RangeStage {
+			category: source.to_owned(),
+			fragments: ranges.len(),
+			ranges: ranges.into_iter().collect(),
+		}];
+
+		for map in path {
+			let previous = &stages.last().unwrap().ranges;
+
+			let fragments: Vec<Interval> = previous.iter()
+				.flat_map(|range| map.map_range_to_destination_ranges(range))
+				.collect();
+
+			stages.push(RangeStage {
+				category: map.destination.clone(),
+				fragments: fragments.len(),
+				ranges: fragments.into_iter().collect(),
+			});
+		}
+
+		Some(stages)
+	}
+
+	/// Every value in `source` that ends up at `value` in `destination`.
+	pub fn reverse_lookup(&self, source: &str, destination: &str, value: usize) -> Option<Vec<usize>> {
+		let path = self.path(source, destination)?;
+
+		let mut values = path.into_iter().rev()
+			.fold(vec![value], |values, map| {
+				values.into_iter()
+					.flat_map(|value| map.reverse_lookup(value))
+					.collect()
+			});
+
+		values.sort();
+		values.dedup();
+		Some(values)
+	}
+
+	/// Every range in `source` that ends up somewhere in `ranges` in `destination`.
+	pub fn reverse_map_ranges(&self, source: &str, destination: &str, ranges: impl IntoIterator<Item=Interval>) -> Option<IntervalSet> {
+		let path = self.path(source, destination)?;
+
+		Some(path.into_iter().rev()
+			.fold(ranges.into_iter().collect::<IntervalSet>(), |ranges, map| {
+				ranges.iter()
+					.map(|range| map.reverse_map_range(range))
+					.fold(IntervalSet::new(), |sources, range_sources| sources.union(&range_sources))
+			}))
+	}
+
+	/// Finds the smallest location reachable from any seed range by searching backwards from location 0,
+	/// growing the searched locations until some of them lead back to a seed.
+	pub fn min_location_by_reverse_search(&self) -> Option<usize> {
+		let seed_ranges: IntervalSet = self.seed_ranges.iter().copied().collect();
+
+		let reaches_seeds = |end: u128| {
+			let locations = Interval::up_to(0, end);
+			let sources = self.reverse_map_ranges("seed", "location", [locations]).unwrap();
+			!sources.intersection(&seed_ranges).is_empty()
+		};
+
+		// Exponential search for some `end` where [0, end) reaches a seed. The domain's size is a power of two
+		let mut end = 1;
+		while !reaches_seeds(end) {
+			if end == DOMAIN_END {
+				return None
+			}
+
+			end *= 2;
+		}
+
+		// Then binary search for the smallest such `end`. [0, lower) never reaches a seed, [0, upper) always does
+		let (mut lower, mut upper) = (end / 2, end);
+		while upper - lower > 1 {
+			let middle = lower + (upper - lower) / 2;
+			if reaches_seeds(middle) {
+				upper = middle;
+			} else {
+				lower = middle;
+			}
+		}
+
+		// `upper` is at most `DOMAIN_END`, so this fits
+		Some((upper - 1) as usize)
+	}
+
+	/// Follows `value` from `source` to `destination`, recording the entry that mapped it at each step.
+	pub fn trace(&self, source: &str, destination: &str, value: usize) -> Option<Trace<usize>> {
+		let path = self.path(source, destination)?;
+		Some(Trace::build(source, value, &path, |map, value| vec![(map.lookup(value), map.lookup_entry(value).into())]))
+	}
+
+	/// Follows `range` from `source` to `destination`. Each step can split a range into several,
+	/// so the result is a tree with one branch per fragment.
+	pub fn trace_range(&self, source: &str, destination: &str, range: Interval) -> Option<Trace<Interval>> {
+		let path = self.path(source, destination)?;
+		Some(Trace::build(source, range, &path, |map, range| map.map_range_with_handlers(range).collect()))
+	}
+
+	/// A single map equivalent to following every map from `source` to `destination`.
+	pub fn composed_map(&self, source: &str, destination: &str) -> Option<Map> {
+		let path = self.path(source, destination)?;
+
+		Some(path.into_iter()
+			.fold(Map::identity(source), |composed, map| composed.compose(map)))
+	}
+
+	pub fn location_for_seed(&self, seed_id: usize) -> usize {
+		self.lookup("seed", "location", seed_id)
+			.expect("no maps lead from seed to location")
+	}
+}
+
+
+/// Maps `source_range` onto the range of the same length starting at `destination_start`.
+/// Neither range goes past `usize::MAX`, so offsetting a value between them can't overflow.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone)]
+pub struct MapEntry {
+	source_range: Interval,
+	destination_start: usize,
+}
+
+impl MapEntry {
+	/// None if either range would go past `usize::MAX`.
+	pub fn new(destination_start: usize, source_start: usize, length: usize) -> Option<MapEntry> {
+		Interval::checked_from_start_length(destination_start, length)?;
+
+		Some(MapEntry {
+			source_range: Interval::checked_from_start_length(source_start, length)?,
+			destination_start,
+		})
+	}
+
+	fn destination_range(&self) -> Interval {
+		shift(self.source_range, self.source_range.start(), self.destination_start)
+	}
+}
+
+/// Moves `range`, which starts at or after `from`, so that `from` lands on `to`.
+fn shift(range: Interval, from: usize, to: usize) -> Interval {
+	let start = to + (range.start() - from);
+	Interval::up_to(start, start as u128 + range.len())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+	source: String,
+	destination: String,
+	entries: Vec<MapEntry>,
+}
+
+impl Map {
+	pub fn lookup(&self, src: usize) -> usize {
+		match self.lookup_entry(src) {
+			// Entry maps src, so return appropriate dest
+			Some(entry) => entry.destination_start + (src - entry.source_range.start()),
+
+			// If a region isn't mapped, the mapping is 1:1
+			None => src,
+		}
+	}
+
+	/// The entry that maps `src`, or None if it's unmapped.
+	pub fn lookup_entry(&self, src: usize) -> Option<&MapEntry> {
+		// Find the last entry with source_start <= src
+		let entry_index = self.entries.partition_point(|e| e.source_range.start() <= src).checked_sub(1)?;
+
+		// src must be >= entry.source_start here
+		let entry = &self.entries[entry_index];
+		entry.source_range.contains(src).then_some(entry)
+	}
+
+	fn entries_containing_range(&self, input_range: Interval) -> &[MapEntry] {
+		// Find the first entry that _may_ contain src_begin
+		let entries_begin = self.entries
+			.binary_search_by(|entry| {
+				use std::cmp::Ordering;
+
+				match entry.source_range.start().cmp(&input_range.start()) {
+					Ordering::Greater => Ordering::Greater,
+
+					_ => {
+						if entry.source_range.contains(input_range.start()) {
+							Ordering::Equal
+						} else {
+							Ordering::Less
+						}
+					}
+				}
+			})
+			.unwrap_or_else(|insertion_point| insertion_point);
+
+		// Find the entry after the one that _may_ contain src_end
+		let entries_end = self.entries[entries_begin..]
+			.binary_search_by(|entry| {
+				use std::cmp::Ordering;
+
+				// The end is at most `DOMAIN_END`, so the last value fits in a usize
+				if entry.source_range.contains(input_range.end().saturating_sub(1) as usize) {
+					Ordering::Equal
+				} else {
+					entry.source_range.end().cmp(&input_range.end())
+				}
+			})
+			.map_or_else(|insertion_point| insertion_point.saturating_sub(0), |exact_match| exact_match + 1);
+
+		&self.entries[entries_begin..entries_begin + entries_end]
+	}
+
+	// [0, 9]
+	// [2, 4] [6, 10]
+	// -> [0, 1] [2, 4] [5, 5] [6, 9]
+
+	fn map_range_to_destination_ranges(&self, input_range: Interval) -> impl Iterator<Item=Interval> + '_ {
+		self.map_range_with_handlers(input_range)
+			.map(|(range, _)| range)
+	}
+
+	/// Maps every range in `ranges`, then sorts and merges the results.
+	pub fn map_range_set(&self, ranges: &IntervalSet) -> IntervalSet {
+		ranges.iter()
+			.flat_map(|range| self.map_range_to_destination_ranges(range))
+			.collect()
+	}
+
+	/// Like `map_range_to_destination_ranges`, along with whatever mapped each output range.
+	pub fn map_range_with_handlers(&self, input_range: Interval) -> impl Iterator<Item=(Interval, Handler)> + '_ {
+		let mut remaining = input_range;
+		let mut output_ranges = Vec::new();
+
+		for entry in self.entries_containing_range(input_range) {
+			// Anything before the entry is an unmapped region, then the entry maps whatever it covers
+			let (unmapped, rest) = remaining.split_at(entry.source_range.start() as u128);
+			let (mapped, rest) = rest.split_at(entry.source_range.end());
+
+			if !unmapped.is_empty() {
+				output_ranges.push((unmapped, Handler::Identity));
+			}
+
+			if !mapped.is_empty() {
+				let destination_range = shift(mapped, entry.source_range.start(), entry.destination_start);
+				output_ranges.push((destination_range, Handler::Entry(*entry)));
+			}
+
+			remaining = rest;
+		}
+
+		// If we're out of entries and there's still range left, its unmapped so output it verbatim
+		if !remaining.is_empty() {
+			output_ranges.push((remaining, Handler::Identity));
+		}
+
+		output_ranges.into_iter()
+	}
+
+	/// Every source value that maps to `dest`. There can be more than one, since
+	/// an entry can map onto a value that is also left unmapped in the source.
+	pub fn reverse_lookup(&self, dest: usize) -> Vec<usize> {
+		let mut sources: Vec<usize> = self.entries.iter()
+			.filter(|entry| entry.destination_range().contains(dest))
+			.map(|entry| entry.source_range.start() + (dest - entry.destination_start))
+			.collect();
+
+		// Unmapped values map to themselves
+		if !self.entries.iter().any(|entry| entry.source_range.contains(dest)) {
+			sources.push(dest);
+		}
+
+		sources.sort();
+		sources
+	}
+
+	/// Every source range that maps into `dest_range`, sorted and merged.
+	pub fn reverse_map_range(&self, dest_range: Interval) -> IntervalSet {
+		let mut sources: IntervalSet = self.entries.iter()
+			.filter_map(|entry| {
+				let overlap = entry.destination_range().intersection(dest_range)?;
+				Some(shift(overlap, entry.destination_start, entry.source_range.start()))
+			})
+			.collect();
+
+		// Unmapped values map to themselves
+		let mapped: IntervalSet = self.entries.iter()
+			.map(|entry| entry.source_range)
+			.collect();
+
+		let unmapped = IntervalSet::from_iter([dest_range]).difference(&mapped);
+		sources = sources.union(&unmapped);
+		sources
+	}
+
+	/// Sorts `entries`, and cuts overlapping entries down so every value is mapped by at most one entry.
+	/// Where entries overlap, the one that starts later wins, the same as in `lookup`.
+	fn new(source: &str, destination: &str, mut entries: Vec<MapEntry>) -> Map {
+		entries.sort();
+
+		for index in 1..entries.len() {
+			let next_start = entries[index].source_range.start() as u128;
+			let previous_range = &mut entries[index - 1].source_range;
+			*previous_range = previous_range.split_at(next_start).0;
+		}
+
+		entries.retain(|entry| !entry.source_range.is_empty());
+
+		Map {
+			source: source.to_owned(),
+			destination: destination.to_owned(),
+			entries,
+		}
+	}
+
+	/// A map with no entries, which maps every value in `category` to itself.
+	pub fn identity(category: &str) -> Map {
+		Map {
+			source: category.to_owned(),
+			destination: category.to_owned(),
+			entries: Vec::new(),
+		}
+	}
+
+	/// Every piece of the source domain and where it starts in the destination,
+	/// including the unmapped gaps between entries which map 1:1.
+	fn pieces(&self) -> impl Iterator<Item=(Interval, usize)> + '_ {
+		let mut unmapped = Interval::starting_at(0);
+
+		self.entries.iter()
+			.flat_map(move |entry| {
+				let (gap, rest) = unmapped.split_at(entry.source_range.start() as u128);
+				unmapped = rest.split_at(entry.source_range.end()).1;
+
+				[(gap, gap.start()), (entry.source_range, entry.destination_start)]
+			})
+			.chain(std::iter::once_with(move || {
+				let last_end = self.entries.last().map_or(0, |entry| entry.source_range.end());
+				let gap = Interval::starting_at(0).split_at(last_end).1;
+				(gap, gap.start())
+			}))
+			.filter(|(range, _)| !range.is_empty())
+	}
+
+	/// A single map equivalent to applying `self` and then `next`.
+	pub fn compose(&self, next: &Map) -> Map {
+		let mut entries: Vec<MapEntry> = Vec::new();
+
+		for (source_range, destination_start) in self.pieces() {
+			let intermediate_range = shift(source_range, source_range.start(), destination_start);
+
+			// Fragments come out in source order, so the source of each fragment directly follows the last
+			let mut remaining_source = source_range;
+
+			for destination_range in next.map_range_to_destination_ranges(intermediate_range) {
+				let (fragment_source, rest) = remaining_source.split_at(remaining_source.start() as u128 + destination_range.len());
+				remaining_source = rest;
+
+				// Identity mappings don't need an entry
+				if destination_range.start() != fragment_source.start() {
+					entries.push(MapEntry {
+						source_range: fragment_source,
+						destination_start: destination_range.start(),
+					});
+				}
+			}
+		}
+
+		entries.sort();
+
+		// Merge entries that continue on from each other in both source and destination
+		entries.dedup_by(|entry, previous| {
+			let continues = previous.source_range.end() == entry.source_range.start() as u128
+				&& previous.destination_range().end() == entry.destination_start as u128;
+
+			if continues {
+				previous.source_range = Interval::up_to(previous.source_range.start(), entry.source_range.end());
+			}
+
+			continues
+		});
+
+		Map {
+			source: self.source.clone(),
+			destination: next.destination.clone(),
+			entries,
+		}
+	}
+}
+
+
+
+
+
+/// Almanacs are equal if they have the same seeds and maps. Issues aren't compared, since they describe the text
+/// an almanac was parsed from and aren't written back out.
+impl PartialEq for Almanac {
+	fn eq(&self, other: &Almanac) -> bool {
+		self.seeds == other.seeds
+			&& self.seed_ranges == other.seed_ranges
+			&& self.maps == other.maps
+	}
+}
+
+impl Eq for Almanac {}
+
+
+/// Writes the almanac back out in the format `Almanac::parse` reads.
+/// Seeds are written as their sorted ranges, so both readings of the seeds survive a round trip.
+impl fmt::Display for Almanac {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "seeds:")?;
+
+		for range in &self.seed_ranges {
+			write!(f, " {} {}", range.start(), range.len())?;
+		}
+
+		for map in &self.maps {
+			write!(f, "\n\n{map}")?;
+		}
+
+		Ok(())
+	}
+}
+
+impl fmt::Display for Map {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}-to-{} map:", self.source, self.destination)?;
+
+		for entry in &self.entries {
+			write!(f, "\n{entry}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Written as `<destination start> <source start> <length>`, the same as in an almanac.
+impl fmt::Display for MapEntry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{} {} {}", self.destination_start, self.source_range.start(), self.source_range.len())
+	}
+}
+
+/// The ranges reached in one category while mapping ranges through an almanac.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeStage {
+	pub category: String,
+	/// How many ranges came out of the previous map, before merging.
+	pub fragments: usize,
+	pub ranges: IntervalSet,
+}
+
+impl fmt::Display for RangeStage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {} fragments merged into {} ranges", self.category, self.fragments, self.ranges.intervals().len())
+	}
+}
+
+/// Whatever mapped a value or range from one category to the next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Handler {
+	Entry(MapEntry),
+	/// Nothing in the map covered it, so it kept the same value.
+	Identity,
+}
+
+impl From<Option<&MapEntry>> for Handler {
+	fn from(entry: Option<&MapEntry>) -> Handler {
+		entry.map_or(Handler::Identity, |entry| Handler::Entry(*entry))
+	}
+}
+
+impl fmt::Display for Handler {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Handler::Entry(entry) => write!(f, "entry {entry}"),
+			Handler::Identity => write!(f, "identity"),
+		}
+	}
+}
+
+
+/// A value or range in one category, and everything it became in the categories after it.
+/// Printing a trace shows it as an indented tree, one category per level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace<T> {
+	pub category: String,
+	pub value: T,
+	/// What mapped the parent's value to this one, or None at the root.
+	pub handler: Option<Handler>,
+	pub children: Vec<Trace<T>>,
+}
+
+impl<T: Copy> Trace<T> {
+	fn build(category: &str, value: T, path: &[&Map], step: impl Fn(&Map, T) -> Vec<(T, Handler)> + Copy) -> Trace<T> {
+		Trace::build_step(category, value, None, path, step)
+	}
+
+	fn build_step(category: &str, value: T, handler: Option<Handler>, path: &[&Map], step: impl Fn(&Map, T) -> Vec<(T, Handler)> + Copy) -> Trace<T> {
+		let children = match path {
+			[map, rest @ ..] => step(map, value).into_iter()
+				.map(|(next_value, handler)| Trace::build_step(&map.destination, next_value, Some(handler), rest, step))
+				.collect(),
+
+			[] => Vec::new(),
+		};
+
+		Trace {
+			category: category.to_owned(),
+			value,
+			handler,
+			children,
+		}
+	}
+
+	/// Every value at the end of the trace, in the final category.
+	pub fn leaves(&self) -> Vec<&Trace<T>> {
+		if self.children.is_empty() {
+			return vec![self]
+		}
+
+		self.children.iter()
+			.flat_map(Trace::leaves)
+			.collect()
+	}
+}
+
+impl<T: fmt::Display> Trace<T> {
+	fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+		write!(f, "{}{} {}", "  ".repeat(depth), self.category, self.value)?;
+
+		if let Some(handler) = &self.handler {
+			write!(f, " ({handler})")?;
+		}
+
+		writeln!(f)?;
+
+		for child in &self.children {
+			child.fmt_indented(f, depth + 1)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: fmt::Display> fmt::Display for Trace<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		self.fmt_indented(f, 0)
+	}
+}
+
+
+#[test]
+fn test_lookups() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+	let soil_to_fertilizer = almanac.map("soil", "fertilizer").unwrap();
+	let fertilizer_to_water = almanac.map("fertilizer", "water").unwrap();
+	let water_to_light = almanac.map("water", "light").unwrap();
+	let light_to_temperature = almanac.map("light", "temperature").unwrap();
+	let temperature_to_humidity = almanac.map("temperature", "humidity").unwrap();
+	let humidity_to_location = almanac.map("humidity", "location").unwrap();
+	dbg!(&almanac);
+	
+	assert_eq!(seed_to_soil.lookup(49), 49);
+	assert_eq!(seed_to_soil.lookup(98), 50);
+	assert_eq!(seed_to_soil.lookup(99), 51);
+	assert_eq!(seed_to_soil.lookup(50), 52);
+	assert_eq!(seed_to_soil.lookup(51), 53);
+
+
+	assert_eq!(seed_to_soil.lookup(79), 81);
+	assert_eq!(seed_to_soil.lookup(14), 14);
+	assert_eq!(seed_to_soil.lookup(13), 13);
+	assert_eq!(seed_to_soil.lookup(55), 57);
+
+
+	assert_eq!(soil_to_fertilizer.lookup(81), 81);
+	assert_eq!(fertilizer_to_water.lookup(81), 81);
+	assert_eq!(water_to_light.lookup(81), 74);
+	assert_eq!(light_to_temperature.lookup(74), 78);
+	assert_eq!(temperature_to_humidity.lookup(78), 78);
+	assert_eq!(humidity_to_location.lookup(78), 82);
+
+	assert_eq!(almanac.location_for_seed(14), 43);
+	assert_eq!(almanac.location_for_seed(55), 86);
+	assert_eq!(almanac.location_for_seed(13), 35);
+}
+
+#[test]
+fn test_entries_containing_range() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+	let fertilizer_to_water = almanac.map("fertilizer", "water").unwrap();
+	assert_eq!(seed_to_soil.entries_containing_range(Interval::from_start_length(0, 100)), seed_to_soil.entries);
+
+	assert_eq!(seed_to_soil.entries_containing_range(Interval::from_start_length(100, 100)), &[]);
+	assert_eq!(seed_to_soil.entries_containing_range(Interval::from_start_length(90, 10)), &seed_to_soil.entries);
+	assert_eq!(seed_to_soil.entries_containing_range(Interval::from_start_length(90, 2)), &seed_to_soil.entries[..1]);
+
+	// fertilizer-to-water map:
+	// 0 7 -> 42
+	// 7 4 -> 57
+	// 11 42 -> 0
+	// 53 8 -> 49
+
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(100, 100)), &[]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(0, 10)), &fertilizer_to_water.entries[0..2]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(0, 11)), &fertilizer_to_water.entries[0..2]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(1, 2)), &fertilizer_to_water.entries[0..1]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(1, 7)), &fertilizer_to_water.entries[0..2]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(6, 2)), &fertilizer_to_water.entries[0..2]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(7, 2)), &fertilizer_to_water.entries[1..2]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(60, 6)), &fertilizer_to_water.entries[3..4]);
+	assert_eq!(fertilizer_to_water.entries_containing_range(Interval::from_start_length(61, 6)), &[]);
+}
+
+#[test]
+fn test_map_range_to_destination_ranges() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+	let soil_to_fertilizer = almanac.map("soil", "fertilizer").unwrap();
+
+	let identity_map = seed_to_soil.map_range_to_destination_ranges(Interval::from_start_length(0, 150)) .collect::<Vec<_>>();
+	assert_eq!(identity_map, &[
+		Interval::new(0, 50),
+		Interval::from_start_length(52, 48),
+		Interval::from_start_length(50, 2),
+		Interval::new(100, 150),
+	]);
+
+	let subrange = seed_to_soil.map_range_to_destination_ranges(Interval::from_start_length(45, 10)) .collect::<Vec<_>>();
+	assert_eq!(subrange, &[
+		Interval::new(45, 50),
+		Interval::from_start_length(52, 5),
+	]);
+
+
+	// soil-to-fertilizer map:
+	// 0  x15 -> 39
+	// 15 x37 -> 0
+	// 52 x2  -> 37
+
+	let combined = seed_to_soil.map_range_to_destination_ranges(Interval::from_start_length(0, 150))
+		.flat_map(|range| soil_to_fertilizer.map_range_to_destination_ranges(range))
+		.collect::<Vec<_>>();
+
+	assert_eq!(combined, &[
+		// Interval::new(0, 50),
+		Interval::from_start_length(39, 15),
+		Interval::from_start_length(0, 50-15),
+
+		// Interval::new(52, 100),
+		Interval::from_start_length(37, 2),
+		Interval::new(54, 100),
+
+		// Interval::new(50, 52),
+		Interval::from_start_length(50-15, 2),
+
+		// Interval::new(100, 150),
+		Interval::new(100, 150),
+	]);
+}
+
+
+#[test]
+fn test_min_location() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+
+	let traces: Vec<_> = almanac.seed_ranges.iter()
+		.map(|&range| almanac.trace_range("seed", "location", range).unwrap())
+		.collect();
+
+	for trace in &traces {
+		print!("{trace}");
+	}
+
+	let min_location = traces.iter()
+		.flat_map(Trace::leaves)
+		.map(|leaf| leaf.value.start())
+		.min();
+
+	assert_eq!(min_location, Some(46));
+}
+
+#[test]
+fn test_paths() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+
+	let path: Vec<_> = almanac.path("seed", "humidity").unwrap().into_iter()
+		.map(|map| map.destination.as_str())
+		.collect();
+
+	assert_eq!(path, &["soil", "fertilizer", "water", "light", "temperature", "humidity"]);
+
+	assert_eq!(almanac.path("water", "water").unwrap().len(), 0);
+	assert!(almanac.path("location", "seed").is_none());
+	assert!(almanac.path("seed", "unicorn").is_none());
+
+	// Seed 79 -> soil 81, fertilizer 81, water 81, light 74, temperature 78, humidity 78, location 82
+	assert_eq!(almanac.lookup("seed", "humidity", 79), Some(78));
+	assert_eq!(almanac.lookup("soil", "light", 81), Some(74));
+	assert_eq!(almanac.lookup("seed", "location", 79), Some(82));
+	assert_eq!(almanac.lookup("location", "seed", 82), None);
+
+	let humidity_ranges: Vec<_> = almanac.map_ranges("light", "humidity", [Interval::from_start_length(74, 1)]).unwrap().collect();
+	assert_eq!(humidity_ranges, &[Interval::from_start_length(78, 1)]);
+}
+
+#[test]
+fn test_extra_categories() {
+	let almanac_str = include_str!("day5.reference.txt").to_owned()
+		+ "\n\nlocation-to-planet map:\n1000 0 100"
+		+ "\n\nseed-to-planet map:\n0 0 10";
+
+	let almanac = Almanac::parse(&almanac_str).unwrap();
+
+	// The direct map is shortest
+	assert_eq!(almanac.path("seed", "planet").unwrap().len(), 1);
+	assert_eq!(almanac.lookup("seed", "planet", 79), Some(79));
+	assert_eq!(almanac.lookup("soil", "planet", 81), Some(1082));
+
+	let duplicated_str = almanac_str + "\n\nseed-to-soil map:\n0 0 1";
+	let error = Almanac::parse(&duplicated_str).unwrap_err();
+	assert_eq!((error.line, error.text.as_str()), (41, "seed-to-soil map:"));
+}
+#[test]
+fn test_parse_errors() {
+	let almanac_str = include_str!("day5.reference.txt");
+
+	let error = Almanac::parse(&almanac_str.replace("0 69 1", "0 69 x")).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str(), error.expected), (28, 6, "x", "a number"));
+
+	let error = Almanac::parse(&almanac_str.replace("52 50 48", "52 50")).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (5, 6, "a range length"));
+
+	let error = Almanac::parse(&almanac_str.replace("seeds: 79 14 55 13", "seeds: 79 14 55")).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (1, 16, "a seed range length"));
+
+	let error = Almanac::parse(&almanac_str.replace("water-to-light map:", "water-to-light:")).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (18, 1, "water-to-light:"));
+
+	let truncated = &almanac_str[..almanac_str.find("\n\nhumidity-to-location").unwrap()];
+	assert!(Almanac::parse(truncated).is_ok());
+
+	let error = Day5::parse(truncated).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (29, 7, "maps leading from seed to location"));
+}
+
+#[test]
+fn test_compose() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+	let soil_to_fertilizer = almanac.map("soil", "fertilizer").unwrap();
+
+	let seed_to_fertilizer = seed_to_soil.compose(soil_to_fertilizer);
+	assert_eq!((seed_to_fertilizer.source.as_str(), seed_to_fertilizer.destination.as_str()), ("seed", "fertilizer"));
+
+	for seed in 0..200 {
+		assert_eq!(seed_to_fertilizer.lookup(seed), soil_to_fertilizer.lookup(seed_to_soil.lookup(seed)), "seed {seed}");
+	}
+
+	// Composing with the identity changes nothing
+	assert_eq!(Map::identity("seed").compose(seed_to_soil).entries, seed_to_soil.entries);
+	assert_eq!(seed_to_soil.compose(&Map::identity("soil")).entries, seed_to_soil.entries);
+}
+
+#[test]
+fn test_composed_map() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_location = almanac.composed_map("seed", "location").unwrap();
+
+	for seed in 0..200 {
+		assert_eq!(seed_to_location.lookup(seed), almanac.location_for_seed(seed), "seed {seed}");
+	}
+
+	let min_location = almanac.seed_ranges.iter()
+		.flat_map(|&range| seed_to_location.map_range_to_destination_ranges(range))
+		.map(|range| range.start())
+		.min();
+
+	assert_eq!(min_location, Some(46));
+
+	let puzzle = Day5::parse(include_str!("day5.reference.txt")).unwrap();
+	assert_eq!(puzzle.seed_to_location, seed_to_location);
+	assert_eq!(Day5::part1(&puzzle), 35);
+	assert_eq!(Day5::part2(&puzzle), 46);
+}
+
+#[test]
+fn test_reverse_lookup() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+
+	// 50 98 2
+	// 52 50 48
+	assert_eq!(seed_to_soil.reverse_lookup(50), &[98]);
+	assert_eq!(seed_to_soil.reverse_lookup(81), &[79]);
+	assert_eq!(seed_to_soil.reverse_lookup(10), &[10]);
+
+	// 99 is itself mapped to 51, so only 97 maps to 99
+	assert_eq!(seed_to_soil.reverse_lookup(99), &[97]);
+	assert_eq!(seed_to_soil.reverse_lookup(51), &[99]);
+
+	// 2 is mapped to 12, but 12 is also left unmapped
+	let (overlapping, _) = parse_map("a-to-b map:\n10 0 5").unwrap();
+	assert_eq!(overlapping.reverse_lookup(12), &[2, 12]);
+	assert_eq!(overlapping.reverse_lookup(2), &[]);
+	assert_eq!(overlapping.reverse_map_range(Interval::new(0, 20)).intervals(), &[Interval::new(0, 20)]);
+	assert_eq!(overlapping.reverse_map_range(Interval::new(0, 10)).intervals(), &[Interval::new(5, 10)]);
+
+	for seed in 0..150 {
+		assert!(seed_to_soil.reverse_lookup(seed_to_soil.lookup(seed)).contains(&seed));
+	}
+
+	assert_eq!(almanac.reverse_lookup("seed", "location", 82), Some(vec![79]));
+
+	let seeds = almanac.reverse_lookup("seed", "location", 35).unwrap();
+	assert!(seeds.contains(&13));
+	assert!(seeds.iter().all(|&seed| almanac.location_for_seed(seed) == 35));
+}
+
+#[test]
+fn test_reverse_map_range() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+
+	// [45, 50) is unmapped, [98, 100) maps to [50, 52), and [50, 53) maps to [52, 55)
+	assert_eq!(seed_to_soil.reverse_map_range(Interval::new(45, 55)).intervals(), &[Interval::new(45, 53), Interval::new(98, 100)]);
+	assert_eq!(seed_to_soil.reverse_map_range(Interval::new(100, 110)).intervals(), &[Interval::new(100, 110)]);
+
+	// Every seed that reverse maps into a location range must actually land in it
+	let locations = Interval::new(40, 60);
+	let seeds = almanac.reverse_map_ranges("seed", "location", [locations]).unwrap();
+
+	for seed in 0..200 {
+		assert_eq!(seeds.contains(seed), locations.contains(almanac.location_for_seed(seed)), "seed {seed}");
+	}
+}
+
+#[test]
+fn test_min_location_by_reverse_search() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	assert_eq!(almanac.min_location_by_reverse_search(), Some(46));
+
+	let puzzle = Day5::parse(include_str!("day5.reference.txt")).unwrap();
+	assert_eq!(almanac.min_location_by_reverse_search(), Some(Day5::part2(&puzzle)));
+}
+
+#[test]
+fn test_validation() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	assert_eq!(almanac.issues(), &[]);
+
+	let (_, issues) = parse_map("a-to-b map:\n0 10 5\n20 12 5\n100 30 0\n3 40 5").unwrap();
+	assert_eq!(issues, &[
+		MapIssue { line: 3, kind: MapIssueKind::OverlappingSources { other_line: 2 } },
+		MapIssue { line: 4, kind: MapIssueKind::ZeroLength },
+		MapIssue { line: 5, kind: MapIssueKind::CollidingDestinations { other_line: 2 } },
+	]);
+
+	let almanac_str = include_str!("day5.reference.txt").replace("52 50 48", "52 50 49");
+	let almanac = Almanac::parse(&almanac_str).unwrap();
+	assert_eq!(almanac.issues(), &[MapIssue { line: 4, kind: MapIssueKind::OverlappingSources { other_line: 5 } }]);
+	assert_eq!(almanac.issues()[0].to_string(), "line 4: source range overlaps the entry on line 5");
+
+	let error = Almanac::parse_with(&almanac_str, Validation::Strict).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (4, 1, "50 98 2"));
+}
+
+#[test]
+fn test_overflow() {
+	let max = usize::MAX;
+
+	// Ranges can include usize::MAX in both directions
+	let (map, issues) = parse_map(&format!("a-to-b map:\n0 {} 10\n{} 0 10", max - 9, max - 9)).unwrap();
+	assert_eq!(issues, &[]);
+	assert_eq!(map.lookup(max), 9);
+	assert_eq!(map.lookup(9), max);
+	assert_eq!(map.reverse_lookup(max), &[9]);
+	assert_eq!(map.reverse_map_range(Interval::new(5, 10)).intervals(), &[Interval::starting_at(max - 4)]);
+
+	let ranges: Vec<_> = map.map_range_to_destination_ranges(Interval::starting_at(max - 20)).collect();
+	assert_eq!(ranges, &[Interval::new(max - 20, max - 9), Interval::new(0, 10)]);
+
+	let ranges: Vec<_> = map.map_range_to_destination_ranges(Interval::new(0, 10)).collect();
+	assert_eq!(ranges, &[Interval::starting_at(max - 9)]);
+
+	let round_trip = map.compose(&map);
+	assert_eq!(round_trip.entries, &[]);
+
+	let puzzle = Day5::parse(&format!("seeds: {max} 1\n\nseed-to-location map:\n0 {max} 1")).unwrap();
+	assert_eq!(puzzle.seed_to_location.lookup(max), 0);
+	assert_eq!(puzzle.almanac.location_for_seed(max), 0);
+	assert_eq!(puzzle.almanac.seed_ranges, &[Interval::starting_at(max)]);
+	assert_eq!(puzzle.almanac.min_location_by_reverse_search(), Some(0));
+	assert_eq!((Day5::part1(&puzzle), Day5::part2(&puzzle)), (0, 0));
+	assert_eq!(Almanac::parse(&puzzle.almanac.to_string()).unwrap(), puzzle.almanac);
+
+	let error = parse_map(&format!("a-to-b map:\n0 {max} 2")).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 24, "2"));
+
+	let error = parse_map(&format!("a-to-b map:\n{} 0 11", max - 9)).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 24, "11"));
+
+	let error = Almanac::parse(&format!("seeds: 1 2 {max} 2")).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (1, 33, "2"));
+
+	// A range covering every value can't be written back out as a seed range
+	assert_eq!(Almanac::new([Interval::starting_at(0)], []), None);
+
+	// Values that don't fit in a usize at all
+	let error = parse_map("a-to-b map:\n0 18446744073709551616 2").unwrap_err();
+	assert_eq!((error.line, error.column), (2, 3));
+}
+
+#[test]
+fn test_trace() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+
+	let trace = almanac.trace("seed", "fertilizer", 79).unwrap();
+	assert_eq!(trace.to_string(), "seed 79\n  soil 81 (entry 52 50 48)\n    fertilizer 81 (identity)\n");
+	assert_eq!(trace.leaves()[0].value, 81);
+
+	let trace = almanac.trace("seed", "location", 79).unwrap();
+	assert_eq!(trace.leaves().len(), 1);
+	assert_eq!(trace.leaves()[0].value, almanac.location_for_seed(79));
+
+	// [95, 100) splits around the end of the "50 98 2" entry
+	let trace = almanac.trace_range("seed", "soil", Interval::new(95, 100)).unwrap();
+	assert_eq!(trace.to_string(), String::from("seed [95, 100)\n")
+		+ "  soil [97, 100) (entry 52 50 48)\n"
+		+ "  soil [50, 52) (entry 50 98 2)\n");
+
+	let leaf_lengths: u128 = almanac.trace_range("seed", "location", Interval::new(0, 200)).unwrap()
+		.leaves().iter()
+		.map(|leaf| leaf.value.len())
+		.sum();
+
+	assert_eq!(leaf_lengths, 200);
+	assert!(almanac.trace("seed", "planet", 79).is_none());
+}
+
+
+/// A map and an input range for checking `map_range_to_destination_ranges` against `lookup`.
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RangeMappingCase {
+	/// (destination start, source start, length) for each entry.
+	entries: Vec<(usize, usize, usize)>,
+	input_range: Interval,
+}
+
+#[cfg(test)]
+impl RangeMappingCase {
+	fn random(rng: &mut Rng) -> RangeMappingCase {
+		let start = rng.range(0..120);
+
+		let mut case = RangeMappingCase {
+			entries: Vec::new(),
+			input_range: Interval::new(start, rng.range(start + 1..start + 60)),
+		};
+
+		for _ in 0..rng.range(0..8) {
+			let entry = (rng.range(0..150), rng.range(0..100), rng.range(1..20));
+			case.entries.push(entry);
+
+			if !case.is_valid() {
+				case.entries.pop();
+			}
+		}
+
+		case
+	}
+
+	/// Entries can't be empty, and neither can the input. Entries may overlap.
+	fn is_valid(&self) -> bool {
+		self.entries.iter().all(|&(_, _, length)| length > 0)
+			&& !self.input_range.is_empty()
+	}
+
+	fn map(&self) -> Map {
+		let entries = self.entries.iter()
+			.map(|&(destination_start, source_start, length)| MapEntry::new(destination_start, source_start, length).unwrap())
+			.collect();
+
+		Map::new("a", "b", entries)
+	}
+
+	/// The output ranges, read in order, must hold exactly `lookup` of each input value in order.
+	/// So every input value maps into an output range, and the lengths add up to the input length.
+	fn check(&self) -> Result<(), String> {
+		let map = self.map();
+		let output_ranges: Vec<_> = map.map_range_to_destination_ranges(self.input_range).collect();
+
+		let output_length: u128 = output_ranges.iter().map(Interval::len).sum();
+		if output_length != self.input_range.len() {
+			return Err(format!("output lengths add up to {output_length}, not {}: {output_ranges:?}", self.input_range.len()))
+		}
+
+		if let Some(empty) = output_ranges.iter().find(|range| range.is_empty()) {
+			return Err(format!("empty output range {empty}: {output_ranges:?}"))
+		}
+
+		let values = |range: &Interval| (range.start() as u128..range.end()).map(|value| value as usize);
+		let output_values = output_ranges.iter().flat_map(values);
+
+		for (input, output) in values(&self.input_range).zip(output_values) {
+			let expected = map.lookup(input);
+			if output != expected {
+				return Err(format!("{input} looks up to {expected}, but was mapped to {output}: {output_ranges:?}"))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Slightly simpler versions of this case.
+	fn shrink(&self) -> Vec<RangeMappingCase> {
+		let mut candidates = Vec::new();
+		let (start, end) = (self.input_range.start() as u128, self.input_range.end());
+
+		for index in 0..self.entries.len() {
+			let mut candidate = self.clone();
+			candidate.entries.remove(index);
+			candidates.push(candidate);
+		}
+
+		for (start, end) in [(start + 1, end), (start, end - 1), (start, start + (end - start) / 2)] {
+			let input_range = Interval::up_to(start as usize, end);
+			candidates.push(RangeMappingCase { input_range, ..self.clone() });
+		}
+
+		for index in 0..self.entries.len() {
+			let (destination_start, source_start, length) = self.entries[index];
+
+			let smaller_entries = [
+				(destination_start / 2, source_start, length),
+				(destination_start.saturating_sub(1), source_start, length),
+				(destination_start, source_start.saturating_sub(1), length),
+				(destination_start, source_start, length - 1),
+			];
+
+			for entry in smaller_entries {
+				let mut candidate = self.clone();
+				candidate.entries[index] = entry;
+				candidates.push(candidate);
+			}
+		}
+
+		// Dropping unchanged candidates guarantees shrinking always finishes
+		candidates.retain(|candidate| candidate.is_valid() && candidate != self);
+		candidates
+	}
+}
+
+#[test]
+fn test_range_mapping_matches_lookup() {
+	for seed in 0..2000 {
+		let mut case = RangeMappingCase::random(&mut Rng::new(seed));
+		let Err(mut failure) = case.check() else { continue };
+
+		// Keep taking the first simpler case that still fails, until there are none
+		while let Some((smaller_case, smaller_failure)) = case.shrink().into_iter()
+			.find_map(|candidate| Some((candidate.clone(), candidate.check().err()?)))
+		{
+			case = smaller_case;
+			failure = smaller_failure;
+		}
+
+		let entries: String = case.entries.iter()
+			.map(|(destination_start, source_start, length)| format!("\n{destination_start} {source_start} {length}"))
+			.collect();
+
+		panic!("seed {seed}: mapping {} through\na-to-b map:{entries}\nfailed: {failure}", case.input_range);
+	}
+}
+
+#[test]
+fn test_range_stages() {
+	let almanac = Almanac::parse(include_str!("day5.reference.txt")).unwrap();
+	let stages = almanac.range_stages("seed", "location", almanac.seed_ranges().iter().copied()).unwrap();
+	assert_eq!(stages.last().unwrap().to_string(), "location: 7 fragments merged into 4 ranges");
+
+	let counts: Vec<_> = stages.iter()
+		.map(|stage| (stage.category.as_str(), stage.fragments, stage.ranges.intervals().len()))
+		.collect();
+
+	assert_eq!(counts, &[
+		("seed", 2, 2),
+		("soil", 2, 2),
+		("fertilizer", 2, 2),
+		("water", 3, 3),
+		("light", 3, 3),
+		("temperature", 4, 4),
+		("humidity", 4, 4),
+		("location", 7, 4),
+	]);
+
+	// Merging doesn't change what's covered
+	let unmerged: IntervalSet = almanac.map_ranges("seed", "location", almanac.seed_ranges.iter().copied()).unwrap().collect();
+	let merged = almanac.map_range_set("seed", "location", almanac.seed_ranges.iter().copied()).unwrap();
+	assert_eq!(merged, unmerged);
+	assert_eq!(merged.iter().next().unwrap().start(), 46);
+
+	// Separate sources can map next to each other
+	let seed_to_soil = almanac.map("seed", "soil").unwrap();
+	let seeds: IntervalSet = [Interval::new(50, 60), Interval::new(98, 100)].into_iter().collect();
+	assert_eq!(seed_to_soil.map_range_set(&seeds).intervals(), &[Interval::new(50, 62)]);
+}
+
+#[test]
+fn test_round_trip() {
+	let almanac_str = include_str!("day5.reference.txt");
+	let almanac = Almanac::parse(almanac_str).unwrap();
+
+	let written = almanac.to_string();
+	let reparsed = Almanac::parse(&written).unwrap();
+	assert_eq!(reparsed, almanac);
+	assert_eq!(reparsed.to_string(), written);
+
+	// Entries come out sorted by source, but otherwise as they went in
+	assert!(written.starts_with("seeds: 55 13 79 14\n\nseed-to-soil map:\n52 50 48\n50 98 2\n\nsoil-to-fertilizer map:\n39 0 15\n"));
+	assert_eq!(written.lines().count(), almanac_str.lines().count());
+
+	// A composed almanac gives the same answers
+	let composed = Almanac::new(almanac.seed_ranges.iter().copied(), [almanac.composed_map("seed", "location").unwrap()]).unwrap();
+	let composed = Day5::parse(&composed.to_string()).unwrap();
+	assert_eq!(Day5::part1(&composed), 35);
+	assert_eq!(Day5::part2(&composed), 46);
+
+	let seed_to_soil = almanac.map("seed", "soil").unwrap().clone();
+	assert!(Almanac::new([], [seed_to_soil.clone(), seed_to_soil.clone()]).is_none());
+
+	// Almanacs without seeds can be written out and read back, but can't be solved
+	let seedless = Almanac::new([], [seed_to_soil]).unwrap();
+	assert_eq!(seedless.to_string(), "seeds:\n\nseed-to-soil map:\n52 50 48\n50 98 2");
+	assert_eq!(Almanac::parse(&seedless.to_string()).unwrap(), seedless);
+	assert_eq!(Almanac::parse("seeds:").unwrap(), Almanac::new([], []).unwrap());
+
+	let error = Day5::parse(&seedless.to_string()).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (1, 7, "at least one seed"));
+
+	// Seed ranges of zero length are allowed, as long as at least one range isn't empty
+	let seed_to_location_str = "\n\nseed-to-location map:\n52 50 48";
+	let error = Day5::parse(&format!("seeds: 79 0 3 0{seed_to_location_str}")).unwrap_err();
+	assert_eq!((error.line, error.column, error.expected), (1, 16, "at least one seed range with a non-zero length"));
+
+	let puzzle = Day5::parse(&format!("seeds: 79 0 3 2{seed_to_location_str}")).unwrap();
+	assert_eq!((Day5::part1(&puzzle), Day5::part2(&puzzle)), (0, 3));
+
+	// Issues are left behind by a round trip, but the almanac is otherwise the same
+	let overlapping = Almanac::parse("seeds: 0 12\n\nseed-to-location map:\n100 0 10\n200 5 3").unwrap();
+	let reparsed = Almanac::parse(&overlapping.to_string()).unwrap();
+	assert_eq!(overlapping.issues().len(), 1);
+	assert_eq!(reparsed.issues(), &[]);
+	assert_eq!(reparsed, overlapping);
+}
+
+#[test]
+fn test_overlapping_entries() {
+	let almanac_str = "seeds: 0 12\n\nseed-to-location map:\n100 0 10\n200 5 3";
+	let puzzle = Day5::parse(almanac_str).unwrap();
+
+	assert_eq!(Day5::warnings(&puzzle), &["line 5: source range overlaps the entry on line 4"]);
+
+	// [0, 10) only applies until [5, 8) starts, so 8 onwards is unmapped
+	let almanac = &puzzle.almanac;
+	let values: Vec<_> = (0..12).map(|seed| almanac.location_for_seed(seed)).collect();
+	assert_eq!(values, &[100, 101, 102, 103, 104, 200, 201, 202, 8, 9, 10, 11]);
+
+	for seed in 0..20 {
+		assert_eq!(puzzle.seed_to_location.lookup(seed), almanac.location_for_seed(seed), "seed {seed}");
+	}
+
+	let ranges: Vec<_> = almanac.map_ranges("seed", "location", [Interval::new(0, 12)]).unwrap().collect();
+	assert_eq!(ranges, &[Interval::new(100, 105), Interval::new(200, 203), Interval::new(8, 12)]);
+
+	assert_eq!(Day5::part1(&puzzle), 12);
+	assert_eq!(Day5::part2(&puzzle), 8);
+
+	// Of entries starting at the same place, the longest one is used
+	let (map, _) = parse_map("a-to-b map:\n100 0 10\n200 0 5").unwrap();
+	assert_eq!(map.lookup(3), 103);
+	assert_eq!(map.lookup(7), 107);
+	assert_eq!(map.entries.len(), 1);
+}
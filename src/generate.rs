@@ -0,0 +1,247 @@
+use crate::rng::Rng;
+
+use std::fmt::Write;
+
+
+const NUMBER_WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+const SYMBOLS: &[char] = &['*', '#', '+', '$', '/', '@', '=', '%', '&', '-'];
+const CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+
+/// Generates a random, valid input for `day`, or None if there's no generator for it.
+/// The same size and seed always give the same input.
+///
+/// What `size` means depends on the day:
+/// - day 1: calibration lines
+/// - day 2: games
+/// - day 3: width and height of the schematic
+/// - day 4: scratchcards
+/// - day 5: entries in each almanac map
+pub fn generate(day: u32, size: usize, seed: u64) -> Option<String> {
+	let rng = &mut Rng::new(seed);
+
+	let input = match day {
+		1 => calibration_document(rng, size),
+		2 => games(rng, size),
+		3 => schematic(rng, size),
+		4 => scratchcards(rng, size),
+		5 => almanac(rng, size),
+		_ => return None,
+	};
+
+	Some(input)
+}
+
+
+/// Lines of lowercase letters, digits and spelled out digits. Every line has at least one digit.
+pub fn calibration_document(rng: &mut Rng, lines: usize) -> String {
+	let mut document = String::new();
+
+	for _ in 0..lines {
+		let mut pieces = Vec::new();
+
+		for _ in 0..rng.range(1..8) {
+			let piece = match rng.range(0..3) {
+				0 => rng.choose(&NUMBER_WORDS).to_string(),
+				1 => (0..rng.range(1..6)).map(|_| random_letter(rng)).collect(),
+				_ => rng.range(1..10).to_string(),
+			};
+
+			pieces.push(piece);
+		}
+
+		let digit_index = rng.range(0..pieces.len() + 1);
+		pieces.insert(digit_index, rng.range(1..10).to_string());
+
+		writeln!(document, "{}", pieces.concat()).unwrap();
+	}
+
+	document
+}
+
+/// `Game <id>: ...` lines, each with up to six sets of red, green and blue cube counts.
+pub fn games(rng: &mut Rng, count: usize) -> String {
+	let mut document = String::new();
+
+	for id in 1..=count {
+		let sets: Vec<String> = (0..rng.range(1..7))
+			.map(|_| {
+				let mut colours = ["red", "green", "blue"];
+				rng.shuffle(&mut colours);
+
+				colours[..rng.range(1..4)].iter()
+					.map(|colour| format!("{} {colour}", rng.range(1..21)))
+					.collect::<Vec<_>>()
+					.join(", ")
+			})
+			.collect();
+
+		writeln!(document, "Game {id}: {}", sets.join("; ")).unwrap();
+	}
+
+	document
+}
+
+/// A square engine schematic of numbers and symbols, with '.' everywhere else.
+pub fn schematic(rng: &mut Rng, side: usize) -> String {
+	let mut rows = vec![vec!['.'; side]; side];
+
+	for row in &mut rows {
+		let mut x = rng.range(0..4);
+
+		// Numbers are always separated by at least one cell so they don't run together
+		while x < side {
+			let value = rng.range(1..1000).to_string();
+			if x + value.len() > side {
+				break
+			}
+
+			for (cell, digit) in row[x..].iter_mut().zip(value.chars()) {
+				*cell = digit;
+			}
+
+			x += value.len() + rng.range(1..8);
+		}
+	}
+
+	for row in &mut rows {
+		for cell in row.iter_mut().filter(|cell| **cell == '.') {
+			if rng.chance(1, 12) {
+				*cell = *rng.choose(SYMBOLS);
+			}
+		}
+	}
+
+	let mut document = String::new();
+	for row in rows {
+		writeln!(document, "{}", row.into_iter().collect::<String>()).unwrap();
+	}
+
+	document
+}
+
+/// Scratchcards with 10 winning numbers and 25 numbers you have.
+/// Most cards win nothing, so the copy counts stay reasonable. Cards near the end can win cards past the last one.
+pub fn scratchcards(rng: &mut Rng, count: usize) -> String {
+	let mut document = String::new();
+	let id_width = count.to_string().len();
+
+	for id in 1..=count {
+		let num_matches = match rng.chance(1, 2) {
+			true => 0,
+			false => rng.range(0..5),
+		};
+
+		let mut numbers: Vec<usize> = (1..100).collect();
+		rng.shuffle(&mut numbers);
+
+		let (winning, others) = numbers.split_at(10);
+		let mut have: Vec<usize> = winning[..num_matches].iter()
+			.chain(&others[..25 - num_matches])
+			.copied()
+			.collect();
+
+		rng.shuffle(&mut have);
+
+		let format_numbers = |numbers: &[usize]| numbers.iter()
+			.map(|number| format!("{number:>2}"))
+			.collect::<Vec<_>>()
+			.join(" ");
+
+		writeln!(document, "Card {id:>id_width$}: {} | {}", format_numbers(winning), format_numbers(&have)).unwrap();
+	}
+
+	document
+}
+
+/// An almanac with ten seed ranges and a map between each pair of categories from seed to location.
+/// Neither the sources nor the destinations of a map's entries overlap.
+pub fn almanac(rng: &mut Rng, entries_per_map: usize) -> String {
+	const DOMAIN: usize = 1 << 32;
+
+	let mut document = String::from("seeds:");
+
+	for _ in 0..10 {
+		write!(document, " {} {}", rng.range(0..DOMAIN / 2), rng.range(1..DOMAIN / 100)).unwrap();
+	}
+
+	writeln!(document).unwrap();
+
+	// Entries and the gaps between them are small enough that laying them end to end always fits in the domain
+	let max_step = (DOMAIN / (2 * entries_per_map.max(1))).max(2);
+
+	for [source, destination] in CATEGORIES.array_windows() {
+		let lengths: Vec<usize> = (0..entries_per_map).map(|_| rng.range(1..max_step)).collect();
+
+		let lay_out = |rng: &mut Rng, order: &[usize]| {
+			let mut starts = vec![0; order.len()];
+			let mut next_start = 0;
+
+			for &index in order {
+				next_start += rng.range(0..max_step);
+				starts[index] = next_start;
+				next_start += lengths[index];
+			}
+
+			starts
+		};
+
+		let mut order: Vec<usize> = (0..entries_per_map).collect();
+		let source_starts = lay_out(rng, &order);
+
+		rng.shuffle(&mut order);
+		let destination_starts = lay_out(rng, &order);
+
+		write!(document, "\n{source}-to-{destination} map:\n").unwrap();
+
+		for index in 0..entries_per_map {
+			writeln!(document, "{} {} {}", destination_starts[index], source_starts[index], lengths[index]).unwrap();
+		}
+	}
+
+	document
+}
+
+
+fn random_letter(rng: &mut Rng) -> char {
+	(b'a' + rng.range(0..26) as u8) as char
+}
+
+
+
+#[test]
+fn test_generated_inputs_solve() {
+	for seed in 0..20 {
+		for day in crate::DAYS {
+			let input = generate(day.number, 30, seed).unwrap();
+
+			for part in crate::PARTS {
+				let answer = day.run(&input, part)
+					.unwrap_or_else(|error| panic!("day {} seed {seed}: {error}\n{input}", day.number));
+
+				assert!(answer.is_some());
+			}
+		}
+	}
+
+	assert_eq!(generate(1, 10, 5), generate(1, 10, 5));
+	assert_ne!(generate(1, 10, 5), generate(1, 10, 6));
+	assert_eq!(generate(6, 10, 5), None);
+}
+
+#[test]
+fn test_generated_sizes() {
+	let rng = &mut Rng::new(0);
+
+	assert_eq!(calibration_document(rng, 25).lines().count(), 25);
+	assert_eq!(games(rng, 25).lines().last().unwrap().split(':').next(), Some("Game 25"));
+	assert_eq!(scratchcards(rng, 25).lines().count(), 25);
+
+	let schematic = schematic(rng, 25);
+	assert_eq!(schematic.lines().count(), 25);
+	assert!(schematic.lines().all(|line| line.len() == 25));
+
+	let almanac = crate::day5::Almanac::parse(&almanac(rng, 25)).unwrap();
+	assert_eq!(almanac.issues(), &[]);
+	assert_eq!(almanac.map("seed", "soil").unwrap().to_string().lines().count(), 26);
+}
@@ -0,0 +1,307 @@
+use common::*;
+
+use crate::ParseError;
+
+use std::cmp::Ordering;
+
+
+/// A rectangular grid of cells, stored row by row and indexed by position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+	size: Vec2i,
+	cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+	pub fn new(size: Vec2i, fill: T) -> Grid<T>
+		where T: Clone
+	{
+		assert!(size.x >= 0 && size.y >= 0, "grid size can't be negative");
+		Grid { size, cells: vec![fill; (size.x * size.y) as usize] }
+	}
+
+	/// Parses one cell per char, with one row per line. Every row must be the same width.
+	pub fn parse_with(text: &str, expected: &'static str, mut parse_cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+		let mut cells = Vec::new();
+		let mut width = None;
+		let mut height = 0;
+
+		for (y, line) in text.lines().enumerate() {
+			let mut row_width = 0;
+
+			for (index, ch) in line.char_indices() {
+				let cell = parse_cell(ch)
+					.ok_or_else(|| ParseError::new(line, &line[index..index + ch.len_utf8()], expected).offset_lines(y))?;
+
+				cells.push(cell);
+				row_width += 1;
+			}
+
+			if *width.get_or_insert(row_width) != row_width {
+				return Err(ParseError::new(line, line, "a row the same width as the first").offset_lines(y))
+			}
+
+			height += 1;
+		}
+
+		Ok(Grid {
+			size: Vec2i::new(width.unwrap_or(0), height),
+			cells,
+		})
+	}
+
+	pub fn size(&self) -> Vec2i {
+		self.size
+	}
+
+	pub fn width(&self) -> i32 {
+		self.size.x
+	}
+
+	pub fn height(&self) -> i32 {
+		self.size.y
+	}
+
+	pub fn in_bounds(&self, pos: Vec2i) -> bool {
+		(0..self.size.x).contains(&pos.x)
+		&& (0..self.size.y).contains(&pos.y)
+	}
+
+	fn index_of(&self, pos: Vec2i) -> Option<usize> {
+		self.in_bounds(pos)
+			.then(|| (pos.y * self.size.x + pos.x) as usize)
+	}
+
+	fn pos_of(&self, index: usize) -> Vec2i {
+		let index = index as i32;
+		Vec2i::new(index % self.size.x, index / self.size.x)
+	}
+
+	pub fn get(&self, pos: Vec2i) -> Option<&T> {
+		self.index_of(pos).map(|index| &self.cells[index])
+	}
+
+	pub fn get_mut(&mut self, pos: Vec2i) -> Option<&mut T> {
+		self.index_of(pos).map(|index| &mut self.cells[index])
+	}
+
+	/// Every cell and its position, row by row.
+	pub fn iter(&self) -> impl Iterator<Item=(Vec2i, &T)> + '_ {
+		self.cells.iter()
+			.enumerate()
+			.map(|(index, cell)| (self.pos_of(index), cell))
+	}
+
+	pub fn row(&self, y: i32) -> Option<&[T]> {
+		(0..self.size.y).contains(&y)
+			.then(|| &self.cells[(y * self.size.x) as usize..((y + 1) * self.size.x) as usize])
+	}
+
+	pub fn rows(&self) -> impl Iterator<Item=&[T]> + '_ {
+		(0..self.size.y).map(|y| self.row(y).unwrap())
+	}
+
+	pub fn column(&self, x: i32) -> Option<impl Iterator<Item=&T> + '_> {
+		(0..self.size.x).contains(&x)
+			.then(|| self.cells[x as usize..].iter().step_by(self.size.x as usize))
+	}
+
+	pub fn columns(&self) -> impl Iterator<Item=impl Iterator<Item=&T> + '_> + '_ {
+		(0..self.size.x).map(|x| self.column(x).unwrap())
+	}
+
+	/// The in-bounds cells directly above, below, left and right of `pos`.
+	pub fn neighbours4(&self, pos: Vec2i) -> impl Iterator<Item=(Vec2i, &T)> + '_ {
+		let offsets = [
+			Vec2i::new(0, -1),
+			Vec2i::new(-1, 0),
+			Vec2i::new(1, 0),
+			Vec2i::new(0, 1),
+		];
+
+		self.cells_at_offsets(pos, offsets)
+	}
+
+	/// The in-bounds cells surrounding `pos`, including diagonals.
+	pub fn neighbours8(&self, pos: Vec2i) -> impl Iterator<Item=(Vec2i, &T)> + '_ {
+		let offsets = [
+			Vec2i::new(-1, -1),
+			Vec2i::new(0, -1),
+			Vec2i::new(1, -1),
+			Vec2i::new(-1, 0),
+			Vec2i::new(1, 0),
+			Vec2i::new(-1, 1),
+			Vec2i::new(0, 1),
+			Vec2i::new(1, 1),
+		];
+
+		self.cells_at_offsets(pos, offsets)
+	}
+
+	fn cells_at_offsets<const N: usize>(&self, pos: Vec2i, offsets: [Vec2i; N]) -> impl Iterator<Item=(Vec2i, &T)> + '_ {
+		offsets.into_iter()
+			.map(move |offset| pos + offset)
+			.filter_map(|pos| Some((pos, self.get(pos)?)))
+	}
+
+	/// Finds every horizontal run of consecutive cells matching `predicate`, row by row.
+	pub fn row_runs(&self, mut predicate: impl FnMut(&T) -> bool) -> Vec<Run> {
+		let mut runs = Vec::new();
+
+		for (y, row) in self.rows().enumerate() {
+			let mut run_start = None;
+
+			for (x, cell) in row.iter().enumerate() {
+				match (predicate(cell), run_start) {
+					(true, None) => run_start = Some(x),
+					(false, Some(start)) => {
+						runs.push(Run::new(start, x, y));
+						run_start = None;
+					}
+					_ => {}
+				}
+			}
+
+			if let Some(start) = run_start {
+				runs.push(Run::new(start, row.len(), y));
+			}
+		}
+
+		runs
+	}
+}
+
+impl Grid<char> {
+	pub fn parse(text: &str) -> Result<Grid<char>, ParseError> {
+		Grid::parse_with(text, "a char", Some)
+	}
+}
+
+impl<T> std::ops::Index<Vec2i> for Grid<T> {
+	type Output = T;
+
+	fn index(&self, pos: Vec2i) -> &T {
+		self.get(pos).expect("position out of bounds")
+	}
+}
+
+impl<T> std::ops::IndexMut<Vec2i> for Grid<T> {
+	fn index_mut(&mut self, pos: Vec2i) -> &mut T {
+		self.get_mut(pos).expect("position out of bounds")
+	}
+}
+
+
+/// A horizontal span of cells within a single row.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Run {
+	pub start: Vec2i,
+	pub length: i32,
+}
+
+impl Run {
+	fn new(start_x: usize, end_x: usize, y: usize) -> Run {
+		Run {
+			start: Vec2i::new(start_x as i32, y as i32),
+			length: (end_x - start_x) as i32,
+		}
+	}
+
+	pub fn positions(&self) -> impl Iterator<Item=Vec2i> {
+		let start = self.start;
+		(0..self.length).map(move |offset| start + Vec2i::new(offset, 0))
+	}
+
+	/// The run and every cell touching it, including diagonals.
+	pub fn surrounding_bounds(&self) -> Bounds {
+		Bounds {
+			min: self.start - Vec2i::splat(1),
+			max: self.start + Vec2i::new(self.length, 1),
+		}
+	}
+}
+
+
+/// An axis aligned rectangle where both bounds are inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Bounds {
+	pub min: Vec2i,
+	pub max: Vec2i,
+}
+
+impl Bounds {
+	pub fn contains(&self, pos: Vec2i) -> bool {
+		(self.min.x..=self.max.x).contains(&pos.x)
+		&& (self.min.y..=self.max.y).contains(&pos.y)
+	}
+}
+
+
+/// Orders positions row by row, the same order that `Grid` stores and iterates cells in.
+pub fn cmp_vec2i(a: Vec2i, b: Vec2i) -> Ordering {
+	a.y.cmp(&b.y).then(a.x.cmp(&b.x))
+}
+
+
+
+#[test]
+fn test_parse_and_get() {
+	let grid = Grid::parse("ab.\n.cd").unwrap();
+
+	assert_eq!(grid.size(), Vec2i::new(3, 2));
+	assert_eq!(grid.get(Vec2i::new(0, 0)), Some(&'a'));
+	assert_eq!(grid.get(Vec2i::new(2, 1)), Some(&'d'));
+	assert_eq!(grid.get(Vec2i::new(3, 0)), None);
+	assert_eq!(grid.get(Vec2i::new(0, -1)), None);
+	assert_eq!(grid[Vec2i::new(1, 1)], 'c');
+
+	let error = Grid::parse_with("12\n3x", "a digit", |ch| ch.to_digit(10)).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
+
+	let error = Grid::parse_with("12\n345", "a digit", |ch| ch.to_digit(10)).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "345"));
+}
+
+#[test]
+fn test_rows_and_columns() {
+	let grid = Grid::parse("abc\ndef").unwrap();
+
+	let rows: Vec<String> = grid.rows().map(|row| row.iter().collect()).collect();
+	assert_eq!(rows, &["abc", "def"]);
+
+	let columns: Vec<String> = grid.columns().map(|column| column.collect()).collect();
+	assert_eq!(columns, &["ad", "be", "cf"]);
+
+	assert!(grid.row(2).is_none());
+	assert!(grid.column(3).is_none());
+}
+
+#[test]
+fn test_neighbours() {
+	let grid = Grid::parse("abc\ndef\nghi").unwrap();
+
+	let neighbours: String = grid.neighbours4(Vec2i::new(1, 1)).map(|(_, ch)| ch).collect();
+	assert_eq!(neighbours, "bdfh");
+
+	let neighbours: String = grid.neighbours8(Vec2i::new(1, 1)).map(|(_, ch)| ch).collect();
+	assert_eq!(neighbours, "abcdfghi");
+
+	let neighbours: String = grid.neighbours8(Vec2i::new(0, 0)).map(|(_, ch)| ch).collect();
+	assert_eq!(neighbours, "bde");
+}
+
+#[test]
+fn test_row_runs() {
+	let grid = Grid::parse("467..114..\n...*......\n..35..6333").unwrap();
+	let runs = grid.row_runs(char::is_ascii_digit);
+
+	assert_eq!(runs, &[
+		Run { start: Vec2i::new(0, 0), length: 3 },
+		Run { start: Vec2i::new(5, 0), length: 3 },
+		Run { start: Vec2i::new(2, 2), length: 2 },
+		Run { start: Vec2i::new(6, 2), length: 4 },
+	]);
+
+	assert!(runs[0].surrounding_bounds().contains(Vec2i::new(3, 1)));
+	assert!(!runs[1].surrounding_bounds().contains(Vec2i::new(3, 1)));
+}
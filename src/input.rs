@@ -0,0 +1,89 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+
+/// Where a day's puzzle input should be read from.
+#[derive(Debug, Clone, Default)]
+pub enum InputSource {
+	/// The conventional `inputs/dayN.txt` location.
+	#[default]
+	Default,
+	File(PathBuf),
+	Stdin,
+}
+
+impl InputSource {
+	/// Interprets a command line argument - `-` means stdin, anything else is a path.
+	pub fn from_arg(arg: &str) -> InputSource {
+		match arg {
+			"-" => InputSource::Stdin,
+			path => InputSource::File(path.into()),
+		}
+	}
+}
+
+pub fn default_input_path(day: u32) -> PathBuf {
+	PathBuf::from(format!("inputs/day{day}.txt"))
+}
+
+pub fn load_input(day: u32, source: &InputSource) -> Result<String, InputError> {
+	match source {
+		InputSource::Default => read_file(&default_input_path(day)),
+		InputSource::File(path) => read_file(path),
+		InputSource::Stdin => {
+			let mut bytes = Vec::new();
+			std::io::stdin().read_to_end(&mut bytes)
+				.map_err(|error| InputError::Io { origin: "<stdin>".into(), error })?;
+
+			decode("<stdin>", bytes)
+		}
+	}
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+	let bytes = std::fs::read(path)
+		.map_err(|error| match error.kind() {
+			std::io::ErrorKind::NotFound => InputError::NotFound(path.to_owned()),
+			_ => InputError::Io { origin: path.display().to_string(), error },
+		})?;
+
+	decode(&path.display().to_string(), bytes)
+}
+
+fn decode(origin: &str, bytes: Vec<u8>) -> Result<String, InputError> {
+	String::from_utf8(bytes)
+		.map_err(|error| InputError::InvalidUtf8 {
+			origin: origin.to_owned(),
+			byte_offset: error.utf8_error().valid_up_to(),
+		})
+}
+
+
+#[derive(Debug)]
+pub enum InputError {
+	NotFound(PathBuf),
+	Io { origin: String, error: std::io::Error },
+	InvalidUtf8 { origin: String, byte_offset: usize },
+	Malformed { origin: String, line_number: usize, expected: &'static str },
+}
+
+impl fmt::Display for InputError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			InputError::NotFound(path) => write!(f, "input file '{}' not found", path.display()),
+			InputError::Io { origin, error } => write!(f, "failed to read '{origin}': {error}"),
+			InputError::InvalidUtf8 { origin, byte_offset } => write!(f, "'{origin}' is not valid UTF-8 (at byte {byte_offset})"),
+			InputError::Malformed { origin, line_number, expected } => write!(f, "{origin}:{line_number}: expected {expected}"),
+		}
+	}
+}
+
+impl std::error::Error for InputError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			InputError::Io { error, .. } => Some(error),
+			_ => None,
+		}
+	}
+}
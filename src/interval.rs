@@ -0,0 +1,301 @@
+use std::fmt;
+
+
+/// One past `usize::MAX`, which is where an interval including `usize::MAX` ends.
+pub const DOMAIN_END: u128 = usize::MAX as u128 + 1;
+
+
+/// A half-open interval `[start, end)`.
+/// `end` is wider than `start` so an interval can include `usize::MAX`, but it's never past `DOMAIN_END`.
+#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash)]
+pub struct Interval {
+	start: usize,
+	end: u128,
+}
+
+impl Interval {
+	pub fn new(start: usize, end: usize) -> Self {
+		Interval {start, end: end as u128}
+	}
+
+	/// Like `new`, for an `end` that can be past `usize::MAX`. Stops at `DOMAIN_END` if `end` is past it.
+	pub fn up_to(start: usize, end: u128) -> Self {
+		Interval {start, end: end.min(DOMAIN_END)}
+	}
+
+	/// Every value from `start` up to and including `usize::MAX`.
+	pub fn starting_at(start: usize) -> Self {
+		Interval {start, end: DOMAIN_END}
+	}
+
+	/// Stops at `usize::MAX` if `start + length` would go past it.
+	pub fn from_start_length(start: usize, length: usize) -> Self {
+		Interval::up_to(start, start as u128 + length as u128)
+	}
+
+	/// None if `start + length` would go past `usize::MAX`.
+	pub fn checked_from_start_length(start: usize, length: usize) -> Option<Self> {
+		let interval = Interval {start, end: start as u128 + length as u128};
+		(interval.end <= DOMAIN_END).then_some(interval)
+	}
+
+	pub fn start(&self) -> usize {
+		self.start
+	}
+
+	/// At most `DOMAIN_END`.
+	pub fn end(&self) -> u128 {
+		self.end
+	}
+
+	/// Can be one more than `usize::MAX`, if the interval covers every value.
+	pub fn len(&self) -> u128 {
+		self.end.saturating_sub(self.start as u128)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.start as u128 >= self.end
+	}
+
+	pub fn contains(&self, idx: usize) -> bool {
+		self.start <= idx && (idx as u128) < self.end
+	}
+
+	/// The overlapping part of both intervals, or None if they don't overlap.
+	pub fn intersection(&self, other: Interval) -> Option<Interval> {
+		let intersection = Interval {start: self.start.max(other.start), end: self.end.min(other.end)};
+		(!intersection.is_empty()).then_some(intersection)
+	}
+
+	pub fn overlaps(&self, other: Interval) -> bool {
+		self.intersection(other).is_some()
+	}
+
+	/// Whether the two intervals could be merged into one without including anything extra.
+	pub fn overlaps_or_touches(&self, other: Interval) -> bool {
+		self.start as u128 <= other.end && other.start as u128 <= self.end
+	}
+
+	/// Splits the interval into the values below `at`, and the values from `at` on. Either part can be empty.
+	/// `at` can be the end of another interval, so it can be past `usize::MAX`.
+	pub fn split_at(&self, at: u128) -> (Interval, Interval) {
+		let at = at.clamp(self.start as u128, self.end.max(self.start as u128));
+		let below = Interval {start: self.start, end: at};
+
+		let above = match usize::try_from(at) {
+			Ok(at) => Interval {start: at, end: self.end},
+			// Nothing is left above the end of the domain
+			Err(_) => Interval {start: self.start, end: self.start as u128},
+		};
+
+		(below, above)
+	}
+}
+
+impl fmt::Display for Interval {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "[{}, {})", self.start, self.end)
+	}
+}
+
+
+/// A set of values stored as sorted, disjoint intervals.
+/// Overlapping and adjacent intervals are always merged, so the representation is as small as possible.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+	intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+	pub fn new() -> Self {
+		IntervalSet::default()
+	}
+
+	pub fn intervals(&self) -> &[Interval] {
+		&self.intervals
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item=Interval> + '_ {
+		self.intervals.iter().copied()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.intervals.is_empty()
+	}
+
+	/// The number of values in the set.
+	pub fn total_length(&self) -> u128 {
+		self.intervals.iter().map(Interval::len).sum()
+	}
+
+	pub fn contains(&self, value: usize) -> bool {
+		let index = self.intervals.partition_point(|interval| interval.end <= value as u128);
+		self.intervals.get(index).is_some_and(|interval| interval.contains(value))
+	}
+
+	pub fn insert(&mut self, interval: Interval) {
+		if interval.is_empty() {
+			return
+		}
+
+		// Find every interval that overlaps or touches the new one, and replace them all with their union
+		let merge_begin = self.intervals.partition_point(|existing| existing.end < interval.start as u128);
+		let merge_end = merge_begin + self.intervals[merge_begin..].partition_point(|existing| existing.start as u128 <= interval.end);
+
+		let merged = self.intervals[merge_begin..merge_end].iter()
+			.fold(interval, |merged, existing| Interval {start: merged.start.min(existing.start), end: merged.end.max(existing.end)});
+
+		self.intervals.splice(merge_begin..merge_end, [merged]);
+	}
+
+	pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+		let mut union = self.clone();
+		for interval in other.iter() {
+			union.insert(interval);
+		}
+
+		union
+	}
+
+	pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+		let mut intervals = Vec::new();
+		let (mut self_it, mut other_it) = (self.iter().peekable(), other.iter().peekable());
+
+		while let (Some(a), Some(b)) = (self_it.peek(), other_it.peek()) {
+			intervals.extend(a.intersection(*b));
+
+			// Whichever interval ends first can't intersect anything else
+			if a.end <= b.end {
+				self_it.next();
+			} else {
+				other_it.next();
+			}
+		}
+
+		IntervalSet { intervals }
+	}
+
+	pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+		let mut intervals = Vec::new();
+		let mut other_intervals = other.intervals.as_slice();
+
+		for mut remaining in self.iter() {
+			// Skip anything that ends before this interval starts
+			let skip = other_intervals.partition_point(|removed| removed.end <= remaining.start as u128);
+			other_intervals = &other_intervals[skip..];
+
+			for removed in other_intervals {
+				if removed.start as u128 >= remaining.end {
+					break
+				}
+
+				let (before, rest) = remaining.split_at(removed.start as u128);
+				if !before.is_empty() {
+					intervals.push(before);
+				}
+
+				remaining = rest.split_at(removed.end).1;
+			}
+
+			if !remaining.is_empty() {
+				intervals.push(remaining);
+			}
+		}
+
+		IntervalSet { intervals }
+	}
+}
+
+impl FromIterator<Interval> for IntervalSet {
+	fn from_iter<I: IntoIterator<Item=Interval>>(iter: I) -> Self {
+		let mut set = IntervalSet::new();
+		for interval in iter {
+			set.insert(interval);
+		}
+
+		set
+	}
+}
+
+
+
+#[test]
+fn test_interval() {
+	let interval = Interval::from_start_length(5, 3);
+	assert_eq!(interval, Interval::new(5, 8));
+	assert_eq!(interval.len(), 3);
+	assert!(interval.contains(5) && interval.contains(7));
+	assert!(!interval.contains(8) && !interval.contains(4));
+
+	assert_eq!(interval.intersection(Interval::new(7, 20)), Some(Interval::new(7, 8)));
+	assert_eq!(interval.intersection(Interval::new(8, 20)), None);
+	assert!(interval.overlaps_or_touches(Interval::new(8, 20)));
+	assert!(!interval.overlaps_or_touches(Interval::new(9, 20)));
+
+	assert_eq!(interval.split_at(6), (Interval::new(5, 6), Interval::new(6, 8)));
+	assert!(interval.split_at(2).0.is_empty() && interval.split_at(20).1.is_empty());
+	assert_eq!(interval.split_at(20).0, interval);
+
+	// Intervals can reach the end of the domain, and include usize::MAX
+	let top = Interval::starting_at(usize::MAX - 1);
+	assert_eq!(Interval::from_start_length(usize::MAX - 1, 5), top);
+	assert_eq!(Interval::checked_from_start_length(usize::MAX - 1, 1), Some(Interval::new(usize::MAX - 1, usize::MAX)));
+	assert_eq!(Interval::checked_from_start_length(usize::MAX - 1, 2), Some(top));
+	assert_eq!(Interval::checked_from_start_length(usize::MAX - 1, 3), None);
+	assert!(top.contains(usize::MAX));
+	assert_eq!(top.len(), 2);
+	assert_eq!(Interval::starting_at(0).len(), DOMAIN_END);
+	assert_eq!(top.split_at(DOMAIN_END), (top, Interval::new(usize::MAX - 1, usize::MAX - 1)));
+	assert!(top.split_at(DOMAIN_END).1.is_empty());
+
+	assert_eq!(Interval::up_to(5, DOMAIN_END + 10), Interval::starting_at(5));
+	assert_eq!(Interval::up_to(5, 8), interval);
+	assert_eq!((top.start(), top.end()), (usize::MAX - 1, DOMAIN_END));
+
+	assert!(Interval::new(3, 3).is_empty());
+	assert_eq!(Interval::new(4, 3).len(), 0);
+}
+
+#[test]
+fn test_insert_merges() {
+	let mut set = IntervalSet::new();
+	set.insert(Interval::new(10, 20));
+	set.insert(Interval::new(30, 40));
+	set.insert(Interval::new(0, 5));
+	assert_eq!(set.intervals(), &[Interval::new(0, 5), Interval::new(10, 20), Interval::new(30, 40)]);
+
+	// Adjacent intervals merge
+	set.insert(Interval::new(5, 8));
+	assert_eq!(set.intervals(), &[Interval::new(0, 8), Interval::new(10, 20), Interval::new(30, 40)]);
+
+	// Spanning several intervals
+	set.insert(Interval::new(15, 35));
+	assert_eq!(set.intervals(), &[Interval::new(0, 8), Interval::new(10, 40)]);
+
+	set.insert(Interval::new(50, 50));
+	assert_eq!(set.total_length(), 38);
+
+	assert!(set.contains(0) && set.contains(39));
+	assert!(!set.contains(8) && !set.contains(40));
+}
+
+#[test]
+fn test_set_operations() {
+	let a: IntervalSet = [Interval::new(0, 10), Interval::new(20, 30)].into_iter().collect();
+	let b: IntervalSet = [Interval::new(5, 25), Interval::new(28, 40)].into_iter().collect();
+
+	assert_eq!(a.union(&b).intervals(), &[Interval::new(0, 40)]);
+	assert_eq!(a.intersection(&b).intervals(), &[Interval::new(5, 10), Interval::new(20, 25), Interval::new(28, 30)]);
+	assert_eq!(a.difference(&b).intervals(), &[Interval::new(0, 5), Interval::new(25, 28)]);
+	assert_eq!(b.difference(&a).intervals(), &[Interval::new(10, 20), Interval::new(30, 40)]);
+
+	assert!(a.difference(&a).is_empty());
+	assert_eq!(a.difference(&IntervalSet::new()), a);
+	assert!(a.intersection(&IntervalSet::new()).is_empty());
+
+	let everything: IntervalSet = [Interval::starting_at(0)].into_iter().collect();
+	assert_eq!(everything.difference(&a).intervals(), &[Interval::new(10, 20), Interval::starting_at(30)]);
+	assert_eq!(everything.difference(&everything), IntervalSet::new());
+	assert!(everything.contains(usize::MAX));
+	assert_eq!(a.union(&everything), everything);
+}
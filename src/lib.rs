@@ -0,0 +1,53 @@
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+
+pub mod aho_corasick;
+pub mod answers;
+pub mod bench;
+pub mod generate;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod output;
+pub mod parse;
+pub mod rng;
+pub mod solution;
+
+pub use grid::Grid;
+pub use interval::{Interval, IntervalSet};
+pub use parse::ParseError;
+pub use solution::{Solution, DynSolution};
+
+use solution::Erased;
+
+
+pub const PARTS: [u32; 2] = [1, 2];
+
+pub struct Day {
+	pub number: u32,
+	pub solution: &'static dyn DynSolution,
+}
+
+impl Day {
+	/// Parses `input` and solves a single part. Returns None if `part` doesn't exist.
+	pub fn run(&self, input: &str, part: u32) -> Result<Option<String>, ParseError> {
+		let parsed = self.solution.parse(input)?;
+		Ok(self.solution.solve(&*parsed, part))
+	}
+}
+
+pub const DAYS: &[Day] = &[
+	Day { number: 1, solution: &Erased::<day1::Day1>::new() },
+	Day { number: 2, solution: &Erased::<day2::Day2>::new() },
+	Day { number: 3, solution: &Erased::<day3::Day3>::new() },
+	Day { number: 4, solution: &Erased::<day4::Day4>::new() },
+	Day { number: 5, solution: &Erased::<day5::Day5>::new() },
+];
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+	DAYS.iter().find(|day| day.number == number)
+}
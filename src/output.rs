@@ -0,0 +1,142 @@
+use crate::answers::CheckStatus;
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum OutputMode {
+	/// Just the answers, one per line.
+	Plain,
+	/// Labelled answers with timings.
+	#[default]
+	Human,
+	/// A JSON array with one object per solved part.
+	Json,
+}
+
+impl OutputMode {
+	pub fn from_name(name: &str) -> Option<OutputMode> {
+		match name {
+			"plain" => Some(OutputMode::Plain),
+			"human" => Some(OutputMode::Human),
+			"json" => Some(OutputMode::Json),
+			_ => None,
+		}
+	}
+}
+
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+	pub day: u32,
+	pub part: u32,
+	pub answer: String,
+	pub elapsed: Duration,
+
+	/// Only set when answers are being checked against recorded answers.
+	pub check: Option<CheckStatus>,
+}
+
+pub fn write_results(mode: OutputMode, results: &[PartResult], out: &mut impl Write) -> io::Result<()> {
+	match mode {
+		OutputMode::Plain => {
+			for result in results {
+				match &result.check {
+					Some(status) => writeln!(out, "{}\t{}", result.answer, status.name())?,
+					None => writeln!(out, "{}", result.answer)?,
+				}
+			}
+		}
+
+		OutputMode::Human => {
+			for PartResult{day, part, answer, elapsed, check} in results {
+				let check = match check {
+					Some(CheckStatus::Pass) => "  PASS".to_owned(),
+					Some(CheckStatus::Fail{expected}) => format!("  FAIL (expected {expected})"),
+					Some(CheckStatus::Unknown) => "  UNKNOWN".to_owned(),
+					None => String::new(),
+				};
+
+				let elapsed = format!("({elapsed:.2?})");
+				let line = format!("Day {day} part {part}: {answer:<20} {elapsed:<12}{check}");
+				writeln!(out, "{}", line.trim_end())?;
+			}
+		}
+
+		OutputMode::Json => {
+			writeln!(out, "[")?;
+
+			for (index, PartResult{day, part, answer, elapsed, check}) in results.iter().enumerate() {
+				let separator = if index + 1 < results.len() { "," } else { "" };
+				let answer = json_string(answer);
+				let elapsed_us = elapsed.as_micros();
+
+				let check = match check {
+					Some(status @ CheckStatus::Fail{expected}) => format!(", \"status\": \"{}\", \"expected\": {}", status.name(), json_string(expected)),
+					Some(status) => format!(", \"status\": \"{}\"", status.name()),
+					None => String::new(),
+				};
+
+				writeln!(out, "\t{{\"day\": {day}, \"part\": {part}, \"answer\": {answer}, \"elapsed_us\": {elapsed_us}{check}}}{separator}")?;
+			}
+
+			writeln!(out, "]")?;
+		}
+	}
+
+	Ok(())
+}
+
+fn json_string(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len() + 2);
+	escaped.push('"');
+
+	for ch in s.chars() {
+		match ch {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\r' => escaped.push_str("\\r"),
+			'\t' => escaped.push_str("\\t"),
+			c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			c => escaped.push(c),
+		}
+	}
+
+	escaped.push('"');
+	escaped
+}
+
+
+#[test]
+fn test_json_output() {
+	let results = [
+		PartResult { day: 1, part: 1, answer: "142".into(), elapsed: Duration::from_micros(15), check: None },
+		PartResult { day: 1, part: 2, answer: "a \"b\"".into(), elapsed: Duration::from_millis(2), check: None },
+	];
+
+	let mut out = Vec::new();
+	write_results(OutputMode::Json, &results, &mut out).unwrap();
+
+	assert_eq!(String::from_utf8(out).unwrap(), "[\n\
+		\t{\"day\": 1, \"part\": 1, \"answer\": \"142\", \"elapsed_us\": 15},\n\
+		\t{\"day\": 1, \"part\": 2, \"answer\": \"a \\\"b\\\"\", \"elapsed_us\": 2000}\n\
+		]\n");
+}
+
+#[test]
+fn test_json_check_output() {
+	let results = [
+		PartResult { day: 5, part: 1, answer: "35".into(), elapsed: Duration::ZERO, check: Some(CheckStatus::Pass) },
+		PartResult { day: 5, part: 2, answer: "47".into(), elapsed: Duration::ZERO, check: Some(CheckStatus::Fail { expected: "46".into() }) },
+	];
+
+	let mut out = Vec::new();
+	write_results(OutputMode::Json, &results, &mut out).unwrap();
+
+	assert_eq!(String::from_utf8(out).unwrap(), "[\n\
+		\t{\"day\": 5, \"part\": 1, \"answer\": \"35\", \"elapsed_us\": 0, \"status\": \"pass\"},\n\
+		\t{\"day\": 5, \"part\": 2, \"answer\": \"47\", \"elapsed_us\": 0, \"status\": \"fail\", \"expected\": \"46\"}\n\
+		]\n");
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+use std::str::FromStr;
+
+
+/// A parse failure, located by line and column within the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+	/// 1-based line number.
+	pub line: usize,
+	/// 1-based column, counted in chars.
+	pub column: usize,
+	/// The text that couldn't be parsed. Empty if the input ended early.
+	pub text: String,
+	pub expected: &'static str,
+}
+
+impl ParseError {
+	/// Locates `offending` within `source`, which can be a single line or several.
+	/// `offending` must be a subslice of `source`.
+	pub fn new(source: &str, offending: &str, expected: &'static str) -> ParseError {
+		let offset = subslice_offset(source, offending)
+			.expect("offending text must be a subslice of source");
+
+		let preceding = &source[..offset];
+		let line_start = preceding.rfind('\n').map_or(0, |index| index + 1);
+
+		ParseError {
+			line: preceding.matches('\n').count() + 1,
+			column: preceding[line_start..].chars().count() + 1,
+			text: offending.lines().next().unwrap_or("").to_owned(),
+			expected,
+		}
+	}
+
+	/// An error for input that ended before `expected` was found.
+	pub fn at_end(source: &str, expected: &'static str) -> ParseError {
+		ParseError::new(source, &source[source.len()..], expected)
+	}
+
+	/// Adjusts the line number for errors in a line or section that doesn't start the input.
+	pub fn offset_lines(mut self, lines: usize) -> ParseError {
+		self.line += lines;
+		self
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let ParseError{line, column, text, expected} = self;
+
+		if text.is_empty() {
+			write!(f, "line {line}, column {column}: expected {expected}, found end of line")
+		} else {
+			write!(f, "line {line}, column {column}: expected {expected}, found '{text}'")
+		}
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+
+/// The byte offset of `inner` within `outer`, if `inner` points into `outer`.
+pub fn subslice_offset(outer: &str, inner: &str) -> Option<usize> {
+	let offset = (inner.as_ptr() as usize).checked_sub(outer.as_ptr() as usize)?;
+	(offset + inner.len() <= outer.len()).then_some(offset)
+}
+
+/// The number of lines in `outer` before `inner` starts.
+pub fn line_offset(outer: &str, inner: &str) -> usize {
+	let offset = subslice_offset(outer, inner)
+		.expect("inner must be a subslice of outer");
+
+	outer[..offset].matches('\n').count()
+}
+
+/// Parses every line of `input` with `parse_line`, fixing up line numbers in any errors.
+pub fn parse_lines<T>(input: &str, mut parse_line: impl FnMut(&str) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+	input.lines()
+		.enumerate()
+		.map(|(index, line)| parse_line(line).map_err(|error| error.offset_lines(index)))
+		.collect()
+}
+
+/// Parses `s`, a subslice of `source`, as a number.
+pub fn parse_number<T: FromStr>(source: &str, s: &str) -> Result<T, ParseError> {
+	s.parse()
+		.map_err(|_| ParseError::new(source, s, "a number"))
+}
+
+/// Like `str::split_once`, but reports where `delimiter` was expected.
+pub fn split_once<'a>(source: &str, s: &'a str, delimiter: char, expected: &'static str) -> Result<(&'a str, &'a str), ParseError> {
+	s.split_once(delimiter)
+		.ok_or_else(|| ParseError::new(source, &s[s.len()..], expected))
+}
+
+
+#[test]
+fn test_error_location() {
+	let source = "first line\nsecond líne here";
+	let offending = &source[source.find("here").unwrap()..];
+
+	let error = ParseError::new(source, offending, "something else");
+	assert_eq!((error.line, error.column, error.text.as_str()), (2, 13, "here"));
+	assert_eq!(error.to_string(), "line 2, column 13: expected something else, found 'here'");
+
+	let error = ParseError::at_end(source, "more").offset_lines(2);
+	assert_eq!((error.line, error.column, error.text.as_str()), (4, 17, ""));
+	assert_eq!(error.to_string(), "line 4, column 17: expected more, found end of line");
+}
+
+#[test]
+fn test_parse_lines() {
+	let numbers = parse_lines("1\n2\n3", |line| parse_number::<u32>(line, line));
+	assert_eq!(numbers, Ok(vec![1, 2, 3]));
+
+	let error = parse_lines("1\n2\nthree", |line| parse_number::<u32>(line, line)).unwrap_err();
+	assert_eq!((error.line, error.column, error.text.as_str()), (3, 1, "three"));
+}
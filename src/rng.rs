@@ -0,0 +1,72 @@
+use std::ops::Range;
+
+
+/// A small, seedable pseudo-random number generator (SplitMix64).
+/// Not suitable for anything security related, but the same seed always gives the same sequence.
+#[derive(Debug, Clone)]
+pub struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	pub fn new(seed: u64) -> Rng {
+		Rng { state: seed }
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+		z ^ (z >> 31)
+	}
+
+	/// A value in `range`, which must not be empty.
+	pub fn range(&mut self, range: Range<usize>) -> usize {
+		assert!(!range.is_empty(), "can't pick from an empty range");
+
+		let span = (range.end - range.start) as u64;
+		range.start + (self.next_u64() % span) as usize
+	}
+
+	/// True with probability `numerator / denominator`.
+	pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+		self.next_u64() % denominator < numerator
+	}
+
+	pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+		&items[self.range(0..items.len())]
+	}
+
+	/// Fisher-Yates shuffle.
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		for index in (1..items.len()).rev() {
+			items.swap(index, self.range(0..index + 1));
+		}
+	}
+}
+
+
+
+#[test]
+fn test_rng() {
+	let mut a = Rng::new(5);
+	let mut b = Rng::new(5);
+
+	let a_values: Vec<_> = (0..10).map(|_| a.next_u64()).collect();
+	let b_values: Vec<_> = (0..10).map(|_| b.next_u64()).collect();
+	assert_eq!(a_values, b_values);
+	assert_ne!(a_values[0], Rng::new(6).next_u64());
+
+	for _ in 0..1000 {
+		assert!((10..20).contains(&a.range(10..20)));
+	}
+
+	let mut items: Vec<_> = (0..50).collect();
+	a.shuffle(&mut items);
+	assert_ne!(items, (0..50).collect::<Vec<_>>());
+
+	items.sort();
+	assert_eq!(items, (0..50).collect::<Vec<_>>());
+}
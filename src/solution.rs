@@ -0,0 +1,72 @@
+use crate::parse::ParseError;
+
+use std::any::Any;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+
+/// A single day's puzzle, split into a parse step shared by both parts.
+pub trait Solution {
+	type Parsed: 'static;
+	type Part1: Display;
+	type Part2: Display;
+
+	fn parse(input: &str) -> Result<Self::Parsed, ParseError>;
+	fn part1(parsed: &Self::Parsed) -> Self::Part1;
+	fn part2(parsed: &Self::Parsed) -> Self::Part2;
+
+	/// Problems with the input that didn't stop it from being parsed.
+	fn warnings(_parsed: &Self::Parsed) -> Vec<String> {
+		Vec::new()
+	}
+}
+
+
+/// Object safe view of a `Solution`, so that days with different parsed and output types can share a registry.
+pub trait DynSolution: Sync {
+	fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+
+	/// Returns None if `part` doesn't exist.
+	fn solve(&self, parsed: &dyn Any, part: u32) -> Option<String>;
+
+	fn warnings(&self, parsed: &dyn Any) -> Vec<String>;
+}
+
+pub struct Erased<S>(PhantomData<fn() -> S>);
+
+impl<S> Erased<S> {
+	pub const fn new() -> Self {
+		Erased(PhantomData)
+	}
+}
+
+impl<S> Default for Erased<S> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<S: Solution> DynSolution for Erased<S> {
+	fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+		S::parse(input).map(|parsed| Box::new(parsed) as Box<dyn Any>)
+	}
+
+	fn solve(&self, parsed: &dyn Any, part: u32) -> Option<String> {
+		let parsed = downcast_parsed::<S>(parsed);
+
+		match part {
+			1 => Some(S::part1(parsed).to_string()),
+			2 => Some(S::part2(parsed).to_string()),
+			_ => None,
+		}
+	}
+
+	fn warnings(&self, parsed: &dyn Any) -> Vec<String> {
+		S::warnings(downcast_parsed::<S>(parsed))
+	}
+}
+
+fn downcast_parsed<S: Solution>(parsed: &dyn Any) -> &S::Parsed {
+	parsed.downcast_ref::<S::Parsed>()
+		.expect("parsed input passed to the wrong solution")
+}